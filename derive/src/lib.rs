@@ -0,0 +1,128 @@
+// For the full copyright and license information, please view the LICENSE
+// file that was distributed with this source code.
+
+//! The derive macro for the `ToRow` trait of `term_grid`, which is enabled
+//! with its `derive` feature.
+
+#![warn(missing_docs)]
+
+use proc_macro::{Delimiter, Spacing, TokenStream, TokenTree};
+
+/// Implements `ToRow` for a struct, with a field of the row for every field
+/// of the struct, in the order they are declared.
+///
+/// Every field is converted with its [`Display`](std::fmt::Display)
+/// implementation. Generic structs and enums are not supported.
+#[proc_macro_derive(ToRow)]
+pub fn derive_to_row(input: TokenStream) -> TokenStream {
+    match to_row_impl(input) {
+        Ok(output) => output,
+        Err(message) => format!("::std::compile_error!({message:?});")
+            .parse()
+            .expect("the error is valid Rust"),
+    }
+}
+
+fn to_row_impl(input: TokenStream) -> Result<TokenStream, String> {
+    let mut tokens = input.into_iter();
+
+    // Skip the attributes and visibility up to the name of the struct.
+    let mut name = None;
+    while let Some(token) = tokens.next() {
+        match token {
+            TokenTree::Ident(ident) if ident.to_string() == "struct" => {
+                name = tokens.next();
+                break;
+            }
+            TokenTree::Ident(ident) if matches!(&*ident.to_string(), "enum" | "union") => {
+                return Err("ToRow can only be derived for structs".into());
+            }
+            _ => {}
+        }
+    }
+    let Some(TokenTree::Ident(name)) = name else {
+        return Err("ToRow can only be derived for structs".into());
+    };
+
+    let fields = match tokens.next() {
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => {
+            let mut names = Vec::new();
+            for field in split_fields(group.stream()) {
+                match field_name(&field) {
+                    Some(name) => names.push(name),
+                    None => return Err("ToRow could not find the name of a field".into()),
+                }
+            }
+            names
+        }
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis => {
+            let num_fields = split_fields(group.stream()).len();
+            (0..num_fields).map(|index| index.to_string()).collect()
+        }
+        Some(TokenTree::Punct(punct)) if punct.as_char() == ';' => Vec::new(),
+        Some(TokenTree::Punct(punct)) if punct.as_char() == '<' => {
+            return Err("ToRow cannot be derived for generic structs".into());
+        }
+        _ => return Err("ToRow could not find the fields of the struct".into()),
+    };
+
+    let cells: String = fields
+        .iter()
+        .map(|field| format!("::std::string::ToString::to_string(&self.{field}),"))
+        .collect();
+    let output = format!(
+        "impl ::term_grid::ToRow for {name} {{
+            type Cell = ::std::string::String;
+
+            fn to_row(&self) -> ::std::vec::Vec<::std::string::String> {{
+                ::std::vec![{cells}]
+            }}
+        }}"
+    );
+    output
+        .parse()
+        .map_err(|_| "ToRow could not generate an implementation".into())
+}
+
+/// Splits the fields of a struct on the commas in between them, leaving out
+/// the commas in their types.
+fn split_fields(stream: TokenStream) -> Vec<Vec<TokenTree>> {
+    let mut fields = Vec::new();
+    let mut field = Vec::new();
+    // The depth of angle brackets, which are not groups, unlike the others.
+    let mut depth = 0usize;
+    let mut after_dash = false;
+    for token in stream {
+        if let TokenTree::Punct(punct) = &token {
+            match punct.as_char() {
+                '<' => depth += 1,
+                // The arrow of a function type does not close a bracket.
+                '>' if !after_dash => depth = depth.saturating_sub(1),
+                ',' if depth == 0 => {
+                    fields.push(std::mem::take(&mut field));
+                    continue;
+                }
+                _ => {}
+            }
+            after_dash = punct.as_char() == '-' && punct.spacing() == Spacing::Joint;
+        } else {
+            after_dash = false;
+        }
+        field.push(token);
+    }
+    if !field.is_empty() {
+        fields.push(field);
+    }
+    fields
+}
+
+/// The name of a named field, which is the identifier before the colon.
+fn field_name(field: &[TokenTree]) -> Option<String> {
+    let colon = field
+        .iter()
+        .position(|token| matches!(token, TokenTree::Punct(punct) if punct.as_char() == ':'))?;
+    match field.get(colon.checked_sub(1)?)? {
+        TokenTree::Ident(ident) => Some(ident.to_string()),
+        _ => None,
+    }
+}
@@ -18,6 +18,7 @@ fn main() {
                 direction: Direction::TopToBottom,
                 filling: Filling::Text(" | ".into()),
                 width: 80,
+                ..Default::default()
             },
         );
 
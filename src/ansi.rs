@@ -0,0 +1,98 @@
+// For the full copyright and license information, please view the LICENSE
+// file that was distributed with this source code.
+
+//! Handling of terminal escape sequences in cell contents.
+//!
+//! `textwrap` already skips the most common CSI and OSC sequences when
+//! calculating widths, but it does not know about other escape sequences,
+//! such as character set selection (`ESC ( B`, which `tput sgr0` emits) or
+//! the single-byte C1 form of CSI. This module handles all of them.
+
+#[cfg(feature = "ansi")]
+use std::borrow::Cow;
+use std::iter::Peekable;
+use std::str::CharIndices;
+
+const ESC: char = '\x1b';
+const BEL: char = '\x07';
+const C1_CSI: char = '\u{9b}';
+
+/// An iterator over the characters of a string that are not part of an
+/// escape sequence, along with their byte indices.
+pub struct Visible<'a> {
+    chars: Peekable<CharIndices<'a>>,
+}
+
+/// Returns the characters of the string that are visible in the terminal.
+pub fn visible(s: &str) -> Visible<'_> {
+    Visible {
+        chars: s.char_indices().peekable(),
+    }
+}
+
+impl Iterator for Visible<'_> {
+    type Item = (usize, char);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (index, c) = self.chars.next()?;
+            match c {
+                ESC => match self.chars.next() {
+                    Some((_, '[')) => self.skip_csi(),
+                    Some((_, ']')) => self.skip_osc(),
+                    // Sequences with intermediate bytes, such as `ESC ( B`,
+                    // end with the first byte that is not an intermediate
+                    // byte.
+                    Some((_, ' '..='/')) => {
+                        while self
+                            .chars
+                            .next_if(|(_, c)| (' '..='/').contains(c))
+                            .is_some()
+                        {}
+                        self.chars.next();
+                    }
+                    // Everything else is a two character sequence.
+                    _ => {}
+                },
+                C1_CSI => self.skip_csi(),
+                c => return Some((index, c)),
+            }
+        }
+    }
+}
+
+impl Visible<'_> {
+    /// Skips the parameters of a control sequence, up to and including its
+    /// final byte.
+    fn skip_csi(&mut self) {
+        for (_, c) in self.chars.by_ref() {
+            if ('@'..='~').contains(&c) {
+                break;
+            }
+        }
+    }
+
+    /// Skips an operating system command, such as an OSC 8 hyperlink, up to
+    /// and including the string terminator (`ESC \`) or the BEL character.
+    fn skip_osc(&mut self) {
+        let mut last = ']';
+        for (_, c) in self.chars.by_ref() {
+            if c == BEL || (c == '\\' && last == ESC) {
+                break;
+            }
+            last = c;
+        }
+    }
+}
+
+/// Removes all ANSI escape sequences from the given string.
+///
+/// The string is only copied if it contains an escape sequence.
+#[cfg(feature = "ansi")]
+pub fn strip(s: &str) -> Cow<'_, str> {
+    if !s.contains([ESC, C1_CSI]) {
+        return Cow::Borrowed(s);
+    }
+
+    Cow::Owned(visible(s).map(|(_, c)| c).collect())
+}
@@ -0,0 +1,110 @@
+// For the full copyright and license information, please view the LICENSE
+// file that was distributed with this source code.
+
+//! Tables made out of delimited text, such as CSV or TSV.
+
+use std::fmt;
+
+use crate::{GridOptions, Table, TableOptions};
+
+/// The error returned by [`Table::from_csv`] when the text ends in the
+/// middle of a quoted field.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub struct CsvError {
+    /// The line on which the quoted field starts, starting at 1
+    pub line: usize,
+}
+
+impl fmt::Display for CsvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "quoted field on line {} is not closed", self.line)
+    }
+}
+
+impl std::error::Error for CsvError {}
+
+impl Table<String> {
+    /// Makes a table out of text with a record on every line, whose fields
+    /// are separated by the given delimiter, such as `','` for CSV or `'\t'`
+    /// for TSV.
+    ///
+    /// Fields can be put in double quotes to contain the delimiter, line
+    /// breaks or double quotes, which are then written twice, as in
+    /// [RFC 4180](https://www.rfc-editor.org/rfc/rfc4180). Lines can end with
+    /// `\r\n`, and empty lines are skipped.
+    ///
+    /// ```
+    /// use term_grid::{GridOptions, Table, TableOptions};
+    ///
+    /// let csv = "name,size\n\"notes, old.txt\",12\nrun.sh,2048\n";
+    /// let options = GridOptions::default();
+    /// let table = Table::from_csv(csv, ',', options, TableOptions::default()).unwrap();
+    /// assert_eq!(
+    ///     table.to_string(),
+    ///     "name            size\nnotes, old.txt  12\nrun.sh          2048\n",
+    /// );
+    /// ```
+    pub fn from_csv(
+        text: &str,
+        delimiter: char,
+        options: GridOptions,
+        table_options: TableOptions,
+    ) -> Result<Self, CsvError> {
+        Ok(Self::new(parse(text, delimiter)?, options, table_options))
+    }
+}
+
+/// Splits the text into records of fields.
+fn parse(text: &str, delimiter: char) -> Result<Vec<Vec<String>>, CsvError> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    // Whether the current line has anything on it, even an empty field.
+    let mut started = false;
+    let mut line = 1;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if field.is_empty() => {
+                let quote_line = line;
+                loop {
+                    match chars.next() {
+                        Some('"') if chars.peek() == Some(&'"') => {
+                            chars.next();
+                            field.push('"');
+                        }
+                        Some('"') => break,
+                        Some(c) => {
+                            if c == '\n' {
+                                line += 1;
+                            }
+                            field.push(c);
+                        }
+                        None => return Err(CsvError { line: quote_line }),
+                    }
+                }
+                started = true;
+            }
+            c if c == delimiter => {
+                record.push(std::mem::take(&mut field));
+                started = true;
+            }
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                if started || !field.is_empty() {
+                    record.push(std::mem::take(&mut field));
+                    records.push(std::mem::take(&mut record));
+                }
+                started = false;
+                line += 1;
+            }
+            c => field.push(c),
+        }
+    }
+    if started || !field.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    Ok(records)
+}
@@ -10,9 +10,66 @@
 #![deny(unsafe_code)]
 #![doc = include_str!("../README.md")]
 
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::ffi::OsStr;
 use std::fmt;
+use std::io;
+use std::sync::OnceLock;
 use textwrap::core::display_width;
 
+mod ansi;
+#[cfg(feature = "csv")]
+mod csv;
+mod number;
+mod panes;
+mod table;
+mod text;
+mod width;
+
+#[cfg(feature = "csv")]
+pub use csv::CsvError;
+pub use number::NumberFormat;
+pub use panes::{Pane, Panes};
+pub use table::{Aggregate, ColumnGroup, Header, Table, TableOptions, ToRow, Totals};
+
+/// Derives [`ToRow`] for a struct, with a field of the row for every field
+/// of the struct, converted with its `Display` implementation.
+#[cfg(feature = "derive")]
+pub use term_grid_derive::ToRow;
+
+/// Calculates the number of columns the given text takes up in the terminal.
+///
+/// This is the measurement used when [`GridOptions::measure`] is not set.
+pub fn measure(text: &str) -> usize {
+    #[cfg(feature = "ansi")]
+    let text = &*ansi::strip(text);
+
+    display_width(text)
+}
+
+/// Cuts the text so that it takes up at most the given number of columns in
+/// the terminal, and returns it along with the number of columns it takes up.
+///
+/// Grapheme clusters, such as letters with combining accents and emoji
+/// sequences, are never split, nor are wide characters, so the result can be
+/// narrower than the given width. Escape sequences are kept, so colors are
+/// still reset. The text is measured with [`measure`].
+///
+/// ```
+/// assert_eq!(term_grid::truncate("日本語", 5), ("日本".into(), 4));
+/// assert_eq!(term_grid::truncate("e\u{301}te\u{301}", 2), ("e\u{301}t".into(), 2));
+/// ```
+pub fn truncate(text: &str, width: usize) -> (Cow<'_, str>, usize) {
+    let text_width = measure(text);
+    if text_width <= width {
+        return (Cow::Borrowed(text), text_width);
+    }
+
+    let (truncated, width) = text::truncate(text, width, "", measure);
+    (Cow::Owned(truncated), width)
+}
+
 /// Direction cells should be written in: either across or downwards.
 #[derive(PartialEq, Eq, Debug, Copy, Clone)]
 pub enum Direction {
@@ -20,266 +77,4187 @@ pub enum Direction {
     /// column for a new row, like a typewriter.
     LeftToRight,
 
+    /// Starts at the top right and moves leftwards, going back to the last
+    /// column for a new row, for right-to-left scripts.
+    RightToLeft,
+
     /// Starts at the top left and moves downwards, going back to the first
     /// row for a new column, like how `ls` lists files by default.
     TopToBottom,
+
+    /// Starts at the bottom left and moves upwards, going back to the last
+    /// row for a new column, so the last cells of the first column end up at
+    /// the bottom.
+    BottomToTop,
+}
+
+/// How the cells are put together when the grid is displayed.
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub enum Mode {
+    /// Lays the cells out in aligned columns.
+    #[default]
+    Grid,
+
+    /// Writes the cells one after the other with the given separator in
+    /// between, like `ls -m` does with `", "`, and starts a new line when the
+    /// next cell does not fit. The line then ends with the separator without
+    /// its trailing whitespace.
+    ///
+    /// The cells are not aligned, and the direction, alignment and filling
+    /// are not used.
+    Stream(String),
+
+    /// Writes one cell per line, like `ls -1`, whatever the width.
+    SingleColumn,
+}
+
+/// How the number of lines and columns of a grid is chosen.
+#[derive(PartialEq, Eq, Debug, Copy, Clone, Default)]
+pub enum Strategy {
+    /// Starts from a number of lines that surely fits and uses fewer lines
+    /// for as long as the cells keep fitting, like `ls` does.
+    #[default]
+    FewestLines,
+
+    /// Uses as many columns as can fit, even if some layouts with fewer
+    /// columns do not fit.
+    ///
+    /// Cells of very different widths can make a layout with more columns
+    /// fit where one with fewer does not, which [`Strategy::FewestLines`]
+    /// stops at.
+    DensestColumns,
+
+    /// Chooses the number of lines like [`Strategy::FewestLines`], but
+    /// spreads the cells over the columns as evenly as possible with
+    /// [`Direction::TopToBottom`] instead of filling up every column but the
+    /// last.
+    ///
+    /// With 10 cells in 3 columns, the columns get 4, 3 and 3 cells instead
+    /// of 4, 4 and 2.
+    Balanced,
+
+    /// Chooses the number of columns the way GNU `ls -C` and `ls -x` do, so
+    /// that the output is the same as theirs.
+    ///
+    /// Every column, including the last one and any empty ones, counts as at
+    /// least one character wider than the separator, and the most columns
+    /// that fit are used. Cells are laid out in ⌈cells / columns⌉ lines, and
+    /// rows that end early are not padded.
+    Gnu,
+
+    /// Chooses the number of lines that makes the ratio of lines to columns
+    /// closest to the given one, using more lines than needed if that gets
+    /// closer, so that a grid is not very wide and short.
+    ///
+    /// `AspectRatio { rows: 1, columns: 1 }` makes the grid as square as
+    /// possible, in numbers of cells.
+    AspectRatio {
+        /// The number of rows in the ratio
+        rows: usize,
+
+        /// The number of columns in the ratio
+        columns: usize,
+    },
+
+    /// Makes every column as wide as the widest cell and uses as many
+    /// columns as fit, like BSD `ls` and `column` do.
+    ///
+    /// This uses more lines than needed, but gives a perfectly regular grid.
+    /// Every column, including the last one, counts as having a separator
+    /// after it, and a single column is used if fewer than two fit.
+    Bsd,
+}
+
+/// The alignment of a cell within its column.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum Alignment {
+    /// The contents are written at the start of the column and padded on the
+    /// right.
+    Left,
+
+    /// The contents are padded on the left so that they end at the edge of
+    /// the column.
+    Right,
+
+    /// The contents are padded on both sides so that they end up in the
+    /// middle of the column. If the padding cannot be split evenly, the
+    /// extra space goes on the right.
+    Center,
+
+    /// The contents are lined up on the given decimal separator, so that the
+    /// integer parts of numbers in the same column end at the same position.
+    /// Cells without the separator are lined up on their rightmost digit.
+    ///
+    /// The block of lined-up numbers is right-aligned within the column.
+    Decimal(char),
+}
+
+/// What to do with cells that are wider than the width of the grid.
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub enum Overflow {
+    /// Display the cells in full, in a single column that is wider than the
+    /// grid should be.
+    #[default]
+    Extend,
+
+    /// Cut the cells so that they fit, and put the given ellipsis (such as
+    /// `"…"` or `"..."`) at the end to show that they were cut.
+    Truncate(String),
+
+    /// Break the cells onto as many lines as needed, indenting the
+    /// continuation lines by the given number of spaces.
+    ///
+    /// This splits cells on newlines as well, like [`Newlines::Split`].
+    Wrap(usize),
+}
+
+/// How to spread out the columns of a grid that is narrower than it may be,
+/// see [`GridOptions::justify`].
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum Justify {
+    /// Add the same amount of space between every pair of columns.
+    Even,
+
+    /// Add space after every column but the last in proportion to its
+    /// width, so wider columns get more space.
+    Proportional,
+}
+
+/// A line put in between groups of rows, see [`GridOptions::row_separator`].
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct RowSeparator {
+    /// The number of rows in between each separator line
+    pub every: usize,
+
+    /// The text of the separator line, which is repeated to fill the width of
+    /// the grid, such as `"-"`
+    pub text: String,
+}
+
+/// A row after the last row of a grid that summarizes it, see
+/// [`GridOptions::footer`].
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum Footer {
+    /// A line of text below the grid, such as `"42 items, 1.3 GB"`
+    Text(String),
+
+    /// Text for each column of the grid, starting with the first, which is
+    /// aligned like the cells
+    ///
+    /// Text for columns that the grid does not have is left out.
+    Columns(Vec<String>),
+}
+
+/// A line of text above or below a grid, see [`GridOptions::title`] and
+/// [`GridOptions::caption`].
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Caption {
+    /// The text of the line
+    pub text: String,
+
+    /// How to align the text within the width of the grid, where decimals
+    /// are aligned to the right
+    pub alignment: Alignment,
+}
+
+/// Limits on the width of a column of the grid, see [`GridOptions::columns`].
+#[derive(PartialEq, Eq, Debug, Copy, Clone, Default)]
+pub struct ColumnWidth {
+    /// The width below which the column does not shrink, even if its cells
+    /// are narrower
+    pub min: Option<usize>,
+
+    /// The width above which the column does not grow
+    ///
+    /// Cells that are wider are cut to fit, with the ellipsis of
+    /// [`Overflow::Truncate`] if that is set.
+    pub max: Option<usize>,
+}
+
+impl ColumnWidth {
+    /// Parses limits on the widths of columns, with the limits of every
+    /// column separated by commas, starting with the first column.
+    ///
+    /// The limits of a column are written as:
+    ///
+    /// - `*` or nothing, for a column that is as wide as its cells
+    /// - `8`, for a column that is exactly 8 wide
+    /// - `4-`, for a column that is at least 4 wide
+    /// - `-20`, for a column that is at most 20 wide
+    /// - `4-20`, for a column that is between 4 and 20 wide
+    ///
+    /// ```
+    /// use term_grid::ColumnWidth;
+    ///
+    /// let columns = ColumnWidth::parse_list("8,*,-20").unwrap();
+    /// assert_eq!(columns[0], ColumnWidth { min: Some(8), max: Some(8) });
+    /// assert_eq!(columns[1], ColumnWidth::default());
+    /// assert_eq!(columns[2], ColumnWidth { min: None, max: Some(20) });
+    /// ```
+    pub fn parse_list(spec: &str) -> Result<Vec<Self>, ColumnWidthError> {
+        spec.split(',').map(str::parse).collect()
+    }
+}
+
+impl std::str::FromStr for ColumnWidth {
+    type Err = ColumnWidthError;
+
+    /// Parses the limits of a single column, as described for
+    /// [`ColumnWidth::parse_list`].
+    fn from_str(spec: &str) -> Result<Self, ColumnWidthError> {
+        let error = || ColumnWidthError {
+            spec: spec.to_string(),
+        };
+        let width = |text: &str| match text.trim() {
+            "" => Ok(None),
+            text => text.parse().map(Some).map_err(|_| error()),
+        };
+
+        let column = match spec.trim() {
+            "" | "*" => ColumnWidth::default(),
+            spec => match spec.split_once('-') {
+                Some((min, max)) => ColumnWidth {
+                    min: width(min)?,
+                    max: width(max)?,
+                },
+                None => {
+                    let width = width(spec)?;
+                    ColumnWidth {
+                        min: width,
+                        max: width,
+                    }
+                }
+            },
+        };
+        if matches!((column.min, column.max), (Some(min), Some(max)) if min > max) {
+            return Err(error());
+        }
+        Ok(column)
+    }
+}
+
+/// The error returned when limits on the widths of columns cannot be parsed,
+/// see [`ColumnWidth::parse_list`].
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct ColumnWidthError {
+    /// The limits of the column that are not valid
+    pub spec: String,
+}
+
+impl fmt::Display for ColumnWidthError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid column width: {:?}", self.spec)
+    }
+}
+
+impl std::error::Error for ColumnWidthError {}
+
+/// The vertical alignment of a multi-line cell within its row, which matters
+/// when other cells in the row have more lines.
+///
+/// See [`Newlines::Split`].
+#[derive(PartialEq, Eq, Debug, Copy, Clone, Default)]
+pub enum VerticalAlignment {
+    /// The lines of the cell start at the top of the row.
+    #[default]
+    Top,
+
+    /// The lines of the cell are in the middle of the row. If they cannot be
+    /// centered exactly, they are moved up.
+    Middle,
+
+    /// The lines of the cell end at the bottom of the row.
+    Bottom,
+}
+
+/// How to count characters whose width depends on the context, according to
+/// the Unicode East Asian Width property.
+///
+/// Terminals configured for CJK locales usually display these characters
+/// (such as `①` or `α`) as two columns wide, while others use one column.
+#[derive(PartialEq, Eq, Debug, Copy, Clone, Default)]
+pub enum AmbiguousWidth {
+    /// Count ambiguous characters as one column wide.
+    #[default]
+    Narrow,
+
+    /// Count ambiguous characters as two columns wide.
+    Wide,
+}
+
+/// How to count emoji sequences: emoji joined with zero width joiners (such
+/// as `👩‍🔬`), or followed by variation selectors or skin tone modifiers.
+///
+/// Terminals differ in how they display these sequences, so the right choice
+/// depends on the terminal the grid is shown in. Emoji that are not part of a
+/// sequence are always counted by their own width.
+#[derive(PartialEq, Eq, Debug, Copy, Clone, Default)]
+pub enum EmojiWidth {
+    /// Add up the widths of all the characters in the sequence, as terminals
+    /// without support for emoji sequences display them one by one.
+    #[default]
+    Separate,
+
+    /// Count every sequence as two columns wide, as terminals that combine
+    /// them into a single glyph do. Sequences ending in the text
+    /// presentation selector count as one column.
+    Joined,
+
+    /// Count every sequence as one column wide.
+    Narrow,
+}
+
+/// What to do with control characters (`\x00` to `\x1f` and `\x7f`) in cells.
+///
+/// Control characters can move the cursor or change the state of the
+/// terminal, so cells from untrusted sources, such as file names, should not
+/// be displayed as they are. Note that the escape character is a control
+/// character as well, so colored cells should only be used with
+/// [`ControlCharacters::Keep`].
+#[derive(PartialEq, Eq, Debug, Copy, Clone, Default)]
+pub enum ControlCharacters {
+    /// Display control characters as they are.
+    #[default]
+    Keep,
+
+    /// Replace every control character by the given character, like `ls -q`
+    /// does with `?`.
+    Replace(char),
+
+    /// Replace control characters by C-style escapes, such as `\n` or
+    /// `\033`.
+    Escape,
+}
+
+/// What to do with newlines in cells.
+#[derive(PartialEq, Eq, Debug, Copy, Clone, Default)]
+pub enum Newlines {
+    /// Display newlines as they are, which breaks the layout of the grid.
+    #[default]
+    Keep,
+
+    /// Replace newlines by `\n`.
+    Escape,
+
+    /// Display every line of the cell on its own terminal line, within the
+    /// column of the cell. Rows containing such cells take up as many
+    /// terminal lines as their tallest cell.
+    Split,
+}
+
+/// How to quote the contents of cells, like the `--quoting-style` option of
+/// `ls`.
+///
+/// Quotes count towards the width of cells. Escape sequences, such as colors,
+/// are left as they are.
+#[derive(PartialEq, Eq, Debug, Copy, Clone, Default)]
+pub enum Quoting {
+    /// Display cells without quotes.
+    #[default]
+    Literal,
+
+    /// Put cells that contain whitespace or shell metacharacters in single
+    /// quotes, so they can be pasted into a shell. Cells containing a single
+    /// quote are put in double quotes instead if that is enough.
+    Shell,
+
+    /// Put every cell in quotes, as with [`Quoting::Shell`].
+    ShellAlways,
+
+    /// Put every cell in double quotes, escaping quotes, backslashes and
+    /// control characters like a C string, like `ls -Q` does.
+    C,
+}
+
+/// Content that can be placed in a [`Grid`].
+///
+/// This is implemented for everything that implements `AsRef<str>`, so
+/// strings can be used directly, as well as for [`Cell`], which allows
+/// attaching extra information to individual cells, and [`OsCell`] and
+/// [`ByteCell`], which hold file names that may not be valid UTF-8.
+/// [`CellRef`] displays cells that are borrowed from elsewhere.
+///
+/// Other types can implement it to be displayed without being converted to
+/// strings first. Implementations must provide at least one of
+/// [`contents`](GridCell::contents) and [`write`](GridCell::write), which
+/// are defined in terms of each other.
+pub trait GridCell {
+    /// The text that should be displayed for this cell.
+    ///
+    /// If this returns an owned string, it is only called once, when the
+    /// grid is created. It is not called at all for cells that know their
+    /// [`width`](GridCell::width), unless the grid needs to look at the text,
+    /// for example to align decimals or escape control characters.
+    ///
+    /// By default, this collects whatever [`write`](GridCell::write) writes.
+    fn contents(&self) -> Cow<'_, str> {
+        let mut text = String::new();
+        self.write(&mut text)
+            .expect("a GridCell implementation returned an error unexpectedly");
+        Cow::Owned(text)
+    }
+
+    /// Writes the text of this cell to the given output.
+    ///
+    /// This is used when the grid is displayed, for cells whose contents are
+    /// not changed by the grid. Cells that know their
+    /// [`width`](GridCell::width) can use it to be formatted lazily, such as
+    /// numbers or styled text.
+    ///
+    /// By default, this writes the [`contents`](GridCell::contents).
+    fn write(&self, f: &mut dyn fmt::Write) -> fmt::Result {
+        f.write_str(&self.contents())
+    }
+
+    /// The exact bytes to write for this cell, if they differ from the
+    /// contents, for example because they are not valid UTF-8.
+    ///
+    /// These are only used by [`Grid::write_to`], as [`fmt::Display`] can
+    /// only write valid UTF-8. They are not used if the contents are changed
+    /// by the grid, for example when control characters are escaped.
+    fn bytes(&self) -> Option<&[u8]> {
+        None
+    }
+
+    /// The display width of this cell, if it is already known.
+    ///
+    /// When this returns `None`, the width is calculated from the contents.
+    /// The width is also calculated if the contents are changed by the grid,
+    /// for example when [`GridOptions::expand_tabs`] is set.
+    fn width(&self) -> Option<usize> {
+        None
+    }
+
+    /// The width below which the column of this cell should not shrink, even
+    /// if the cell itself is narrower.
+    ///
+    /// This keeps columns stable when a grid is displayed again after longer
+    /// cells have been added.
+    fn min_width(&self) -> Option<usize> {
+        None
+    }
+
+    /// Whether this cell should be left out of the grid.
+    ///
+    /// Hidden cells are skipped when the grid is laid out and displayed, but
+    /// the other cells keep their indices.
+    fn hidden(&self) -> bool {
+        false
+    }
+
+    /// The number of adjacent columns this cell takes up, including the
+    /// separators in between them, such as for a label for the cells after
+    /// it.
+    ///
+    /// Spans are only used when the cells go across. A cell that does not
+    /// fit in the rest of its row starts a new row, and a cell never spans
+    /// more columns than the grid has.
+    fn span(&self) -> usize {
+        1
+    }
+
+    /// The alignment of this cell, overriding the default alignment of the
+    /// column it ends up in.
+    fn alignment(&self) -> Option<Alignment> {
+        None
+    }
+
+    /// The vertical alignment of this cell, overriding the default vertical
+    /// alignment of the grid.
+    fn vertical_alignment(&self) -> Option<VerticalAlignment> {
+        None
+    }
+}
+
+impl<T: AsRef<str>> GridCell for T {
+    fn contents(&self) -> Cow<'_, str> {
+        Cow::Borrowed(self.as_ref())
+    }
+}
+
+/// A cell with some contents and extra information about how it should be
+/// displayed.
+///
+/// Cells can be mixed freely with the default column alignment, so names and
+/// counts can be put in the same grid:
+///
+/// ```
+/// use term_grid::{Alignment, Cell};
+///
+/// let cells = vec![
+///     Cell::new("file.txt"),
+///     Cell::new("123").with_alignment(Alignment::Right),
+/// ];
+/// ```
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Cell<T, M = ()> {
+    /// The text of the cell
+    pub contents: T,
+
+    /// The alignment of this cell, if it should differ from the column
+    pub alignment: Option<Alignment>,
+
+    /// The display width of the contents, if it should not be calculated
+    ///
+    /// This is useful when the contents contain text that takes up no space
+    /// in the terminal, but which should not be interpreted as such by this
+    /// library.
+    pub width: Option<usize>,
+
+    /// The width below which the column of this cell should not shrink
+    pub min_width: Option<usize>,
+
+    /// The vertical alignment of this cell, if it should differ from the grid
+    pub vertical_alignment: Option<VerticalAlignment>,
+
+    /// Whether this cell should be left out of the grid
+    pub hidden: bool,
+
+    /// The number of columns this cell takes up, see [`GridCell::span`]
+    pub span: usize,
+
+    /// Arbitrary data attached to this cell, which is not displayed
+    ///
+    /// This can be used to find out what a cell refers to after the layout
+    /// is computed, see [`Grid::positions`].
+    pub data: M,
+}
+
+impl<T> Cell<T> {
+    /// Creates a new cell with the given contents and the default alignment
+    pub fn new(contents: T) -> Self {
+        Self {
+            contents,
+            alignment: None,
+            width: None,
+            min_width: None,
+            vertical_alignment: None,
+            hidden: false,
+            span: 1,
+            data: (),
+        }
+    }
+}
+
+impl<T, M> Cell<T, M> {
+    /// Sets the alignment of this cell
+    pub fn with_alignment(mut self, alignment: Alignment) -> Self {
+        self.alignment = Some(alignment);
+        self
+    }
+
+    /// Sets the display width of this cell, so that it is not calculated
+    /// from the contents
+    pub fn with_width(mut self, width: usize) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    /// Sets the width below which the column of this cell should not shrink
+    pub fn with_min_width(mut self, min_width: usize) -> Self {
+        self.min_width = Some(min_width);
+        self
+    }
+
+    /// Sets the vertical alignment of this cell
+    pub fn with_vertical_alignment(mut self, vertical_alignment: VerticalAlignment) -> Self {
+        self.vertical_alignment = Some(vertical_alignment);
+        self
+    }
+
+    /// Sets whether this cell should be left out of the grid
+    pub fn with_hidden(mut self, hidden: bool) -> Self {
+        self.hidden = hidden;
+        self
+    }
+
+    /// Sets the number of columns this cell takes up
+    pub fn with_span(mut self, span: usize) -> Self {
+        self.span = span;
+        self
+    }
+
+    /// Attaches the given data to this cell, replacing any data it had
+    pub fn with_data<N>(self, data: N) -> Cell<T, N> {
+        Cell {
+            contents: self.contents,
+            alignment: self.alignment,
+            width: self.width,
+            min_width: self.min_width,
+            vertical_alignment: self.vertical_alignment,
+            hidden: self.hidden,
+            span: self.span,
+            data,
+        }
+    }
+}
+
+impl<T> From<T> for Cell<T> {
+    fn from(contents: T) -> Self {
+        Self::new(contents)
+    }
+}
+
+impl Cell<String> {
+    /// Creates a cell containing an OSC 8 hyperlink to the given URL, which
+    /// displays the given text.
+    ///
+    /// Only the text counts towards the width of the cell.
+    pub fn hyperlink(text: &str, url: &str) -> Self {
+        Self::new(format!("\x1b]8;;{url}\x1b\\{text}\x1b]8;;\x1b\\"))
+    }
+}
+
+impl<T: AsRef<str>, M> GridCell for Cell<T, M> {
+    fn contents(&self) -> Cow<'_, str> {
+        Cow::Borrowed(self.contents.as_ref())
+    }
+
+    fn alignment(&self) -> Option<Alignment> {
+        self.alignment
+    }
+
+    fn width(&self) -> Option<usize> {
+        self.width
+    }
+
+    fn min_width(&self) -> Option<usize> {
+        self.min_width
+    }
+
+    fn hidden(&self) -> bool {
+        self.hidden
+    }
+
+    fn span(&self) -> usize {
+        self.span
+    }
+
+    fn vertical_alignment(&self) -> Option<VerticalAlignment> {
+        self.vertical_alignment
+    }
+}
+
+/// A cell containing an OS string, such as a file name or a [`Path`].
+///
+/// Its contents are displayed lossily if they are not valid UTF-8, with the
+/// invalid parts measured as replacement characters. On Unix,
+/// [`Grid::write_to`] writes the original bytes instead.
+///
+/// [`Path`]: std::path::Path
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct OsCell<T>(pub T);
+
+impl<T> From<T> for OsCell<T> {
+    fn from(contents: T) -> Self {
+        Self(contents)
+    }
+}
+
+impl<T: AsRef<OsStr>> GridCell for OsCell<T> {
+    fn contents(&self) -> Cow<'_, str> {
+        self.0.as_ref().to_string_lossy()
+    }
+
+    #[cfg(unix)]
+    fn bytes(&self) -> Option<&[u8]> {
+        use std::os::unix::ffi::OsStrExt;
+
+        Some(self.0.as_ref().as_bytes())
+    }
+}
+
+/// A cell containing raw bytes, such as a file name that is not valid UTF-8
+/// and that should be written exactly as it is.
+///
+/// Its contents are measured and displayed lossily if they are not valid
+/// UTF-8, but [`Grid::write_to`] writes the original bytes.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct ByteCell<T>(pub T);
+
+impl<T> From<T> for ByteCell<T> {
+    fn from(contents: T) -> Self {
+        Self(contents)
+    }
+}
+
+impl<T: AsRef<[u8]>> GridCell for ByteCell<T> {
+    fn contents(&self) -> Cow<'_, str> {
+        String::from_utf8_lossy(self.0.as_ref())
+    }
+
+    fn bytes(&self) -> Option<&[u8]> {
+        Some(self.0.as_ref())
+    }
+}
+
+/// A borrowed cell, which is displayed just like the cell it refers to.
+///
+/// This is what [`Grid::from_slice`] puts in the grid, so cells that are
+/// already stored elsewhere don't have to be cloned or moved to be displayed.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct CellRef<'a, T>(pub &'a T);
+
+impl<'a, T> From<&'a T> for CellRef<'a, T> {
+    fn from(cell: &'a T) -> Self {
+        Self(cell)
+    }
 }
 
-/// The text to put in between each pair of columns.
-///
-/// This does not include any spaces used when aligning cells.
-#[derive(PartialEq, Eq, Debug)]
-pub enum Filling {
-    /// A number of spaces
-    Spaces(usize),
+impl<T: GridCell> GridCell for CellRef<'_, T> {
+    fn contents(&self) -> Cow<'_, str> {
+        self.0.contents()
+    }
+
+    fn write(&self, f: &mut dyn fmt::Write) -> fmt::Result {
+        self.0.write(f)
+    }
+
+    fn bytes(&self) -> Option<&[u8]> {
+        self.0.bytes()
+    }
+
+    fn width(&self) -> Option<usize> {
+        self.0.width()
+    }
+
+    fn min_width(&self) -> Option<usize> {
+        self.0.min_width()
+    }
+
+    fn hidden(&self) -> bool {
+        self.0.hidden()
+    }
+
+    fn span(&self) -> usize {
+        self.0.span()
+    }
+
+    fn alignment(&self) -> Option<Alignment> {
+        self.0.alignment()
+    }
+
+    fn vertical_alignment(&self) -> Option<VerticalAlignment> {
+        self.0.vertical_alignment()
+    }
+}
+
+/// The text to put in between each pair of columns.
+///
+/// This does not include any spaces used when aligning cells.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum Filling {
+    /// A number of spaces
+    Spaces(usize),
+
+    /// An arbitrary string
+    ///
+    /// `"|"` is a common choice.
+    Text(String),
+
+    /// A space, the given character and another space, where the character
+    /// is repeated to also fill the padding around the cells on either side
+    ///
+    /// This connects the cells like the dots in a table of contents:
+    /// `Introduction ..... 1`.
+    Leader(char),
+
+    /// A different filling for each gap between columns, starting with the
+    /// gap after the first column
+    ///
+    /// The last filling is used for the remaining gaps, so
+    /// `PerColumn(vec![Filling::Text(" │ ".into()), Filling::Spaces(2)])`
+    /// puts a line after the first column and two spaces everywhere else.
+    PerColumn(Vec<Filling>),
+
+    /// The given fillings in turn, starting over after the last one
+    ///
+    /// `Cycle(vec![Filling::Text(" │ ".into()), Filling::Text(" ┃ ".into())])`
+    /// alternates between thin and thick lines.
+    Cycle(Vec<Filling>),
+}
+
+/// The filling of gaps that have nothing in a [`Filling::PerColumn`] or
+/// [`Filling::Cycle`] list.
+static NO_FILLING: Filling = Filling::Spaces(0);
+
+/// Splits the given amount of padding into the padding before and after text
+/// with the given alignment, where decimals are aligned like numbers.
+fn split_padding(alignment: Alignment, padding: usize) -> (usize, usize) {
+    match alignment {
+        Alignment::Left => (0, padding),
+        Alignment::Right | Alignment::Decimal(_) => (padding, 0),
+        Alignment::Center => (padding / 2, padding - padding / 2),
+    }
+}
+
+/// Returns the width of the integer part of a cell aligned on the given
+/// decimal separator: everything before the separator or, if there is none,
+/// everything up to and including the last digit.
+///
+/// Escape sequences are skipped, so a separator in the URL of a hyperlink
+/// is not mistaken for the decimal separator.
+fn integer_width(contents: &str, separator: char, measure: impl Fn(&str) -> usize) -> usize {
+    let mut last_digit_end = None;
+    let mut end = None;
+    for (index, c) in ansi::visible(contents) {
+        if c == separator {
+            end = Some(index);
+            break;
+        } else if c.is_ascii_digit() {
+            last_digit_end = Some(index + 1);
+        }
+    }
+
+    let end = end.or(last_digit_end).unwrap_or(contents.len());
+    measure(&contents[..end])
+}
+
+impl Filling {
+    /// The width of the filling, which is the width of its widest gap if it
+    /// differs between gaps.
+    fn width(&self, measure: impl Fn(&str) -> usize + Copy) -> usize {
+        match self {
+            Filling::Spaces(w) => *w,
+            Filling::Text(t) => measure(t),
+            Filling::Leader(_) => 3,
+            Filling::PerColumn(fillings) | Filling::Cycle(fillings) => fillings
+                .iter()
+                .map(|filling| filling.width(measure))
+                .max()
+                .unwrap_or(0),
+        }
+    }
+
+    /// Checks that the filling can be put in between columns.
+    fn check(&self) -> Result<(), LayoutError> {
+        match self {
+            Filling::Text(text) if text.contains('\n') => Err(LayoutError::NewlineInFilling),
+            Filling::PerColumn(fillings) | Filling::Cycle(fillings) if fillings.is_empty() => {
+                Err(LayoutError::EmptyFilling)
+            }
+            Filling::PerColumn(fillings) | Filling::Cycle(fillings) => {
+                fillings.iter().try_for_each(Filling::check)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Whether every gap between columns gets the same filling.
+    fn is_uniform(&self) -> bool {
+        !matches!(self, Filling::PerColumn(_) | Filling::Cycle(_))
+    }
+
+    /// The filling of the gap after column `index`.
+    fn gap(&self, index: usize) -> &Filling {
+        match self {
+            Filling::PerColumn(fillings) => fillings
+                .get(index)
+                .or(fillings.last())
+                .unwrap_or(&NO_FILLING)
+                .gap(0),
+            Filling::Cycle(fillings) if !fillings.is_empty() => {
+                fillings[index % fillings.len()].gap(index / fillings.len())
+            }
+            Filling::Cycle(_) => &NO_FILLING,
+            filling => filling,
+        }
+    }
+}
+
+/// The options for a grid view that should be passed to [`Grid::new`]
+///
+/// Functions in the options are compared by their addresses, so the same
+/// function can make options unequal if it ends up with several addresses.
+#[allow(unknown_lints, unpredictable_function_pointer_comparisons)]
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct GridOptions {
+    /// The direction that the cells should be written in
+    pub direction: Direction,
+
+    /// Whether the cells are laid out in columns or written as a stream
+    pub mode: Mode,
+
+    /// The string to put in between each column of cells
+    pub filling: Filling,
+
+    /// The width to fill with the grid
+    pub width: usize,
+
+    /// The alignment of cells that do not specify their own
+    ///
+    /// With [`Alignment::Right`], the last column is padded as well, so that
+    /// all cells end flush against their column boundary.
+    pub alignment: Alignment,
+
+    /// The function used to calculate the display width of cells and of the
+    /// filling, instead of [`measure`]
+    ///
+    /// Cells that specify their own width through [`GridCell::width`] are not
+    /// measured at all.
+    pub measure: Option<fn(&str) -> usize>,
+
+    /// How to count characters with an ambiguous width
+    ///
+    /// This is ignored if [`GridOptions::measure`] is set.
+    pub ambiguous_width: AmbiguousWidth,
+
+    /// How to count emoji sequences
+    ///
+    /// This is ignored if [`GridOptions::measure`] is set.
+    pub emoji_width: EmojiWidth,
+
+    /// Replace tabs in cells by spaces up to the next multiple of the given
+    /// tab size, which is usually 8
+    ///
+    /// Tab stops are counted from the start of each cell. Tabs are left as
+    /// they are when this is `None`, which breaks the alignment of the grid.
+    pub expand_tabs: Option<usize>,
+
+    /// What to do with control characters in cells
+    ///
+    /// Tabs are expanded before this is applied if [`GridOptions::expand_tabs`]
+    /// is set.
+    pub control_characters: ControlCharacters,
+
+    /// What to do with newlines in cells
+    ///
+    /// Newlines are kept for [`GridOptions::control_characters`] if this is
+    /// set to [`Newlines::Split`].
+    pub newlines: Newlines,
+
+    /// The vertical alignment of multi-line cells that do not specify their
+    /// own
+    pub vertical_alignment: VerticalAlignment,
+
+    /// What to do with cells that are wider than [`GridOptions::width`]
+    pub overflow: Overflow,
+
+    /// The number of spaces that [`Filling::Spaces`] may be reduced to if
+    /// that means the cells fit in fewer lines
+    ///
+    /// The spacing is only reduced as much as needed. This has no effect for
+    /// the other kinds of filling.
+    pub minimum_spacing: Option<usize>,
+
+    /// How to quote the contents of cells
+    ///
+    /// Cells are quoted after [`GridOptions::control_characters`] is applied
+    /// and before they are cut to fit by [`GridOptions::overflow`].
+    pub quoting: Quoting,
+
+    /// Put the number of every cell before it, starting at 1, so that cells
+    /// can be picked by number
+    ///
+    /// The numbers are right-aligned to the width of the largest one and
+    /// followed by a space, and count towards the width of the cells. They
+    /// are the indices of the cells plus one, so hidden cells leave gaps. A
+    /// [`Table`] numbers its rows in a column of its own instead.
+    pub numbered: bool,
+
+    /// The character used to pad cells to the width of their column, which
+    /// is a space by default
+    ///
+    /// This should be a character that takes up a single column, such as
+    /// `.` or `·`. It is not used for [`Filling::Spaces`].
+    pub padding: char,
+
+    /// The largest number of columns to use, even if more would fit
+    pub max_columns: Option<usize>,
+
+    /// Limits on the widths of the columns, starting with the first column
+    ///
+    /// Columns that are not in the list can be as wide as their cells.
+    pub columns: Vec<ColumnWidth>,
+
+    /// Make every column as wide as the widest cell, like BSD `column` does
+    ///
+    /// This uses more lines than needed, but gives a perfectly regular grid.
+    /// [`Strategy::Bsd`] does the same, but works out the number of columns
+    /// the way BSD `ls` does.
+    pub equal_columns: bool,
+
+    /// Start every column at a multiple of the given tab size, like GNU
+    /// `ls -T` does, so that the spaces before columns can be turned into tabs
+    /// without breaking the alignment
+    ///
+    /// The columns get wider to reach the next tab stop.
+    pub tab_stops: Option<usize>,
+
+    /// Write the spaces in between cells with tabs of the given size where
+    /// they reach a tab stop, which makes large listings smaller
+    ///
+    /// This is only done when the padding and the filling are spaces. Tab
+    /// stops are counted from the start of the line, including the
+    /// indentation.
+    pub padding_tabs: Option<usize>,
+
+    /// Spread out the columns so that the grid takes up the whole width,
+    /// except for the last terminal column, as with the default layout
+    ///
+    /// The extra space goes after the cells, so it is filled with
+    /// [`GridOptions::padding`].
+    pub justify: Option<Justify>,
+
+    /// How to choose the number of lines and columns
+    pub strategy: Strategy,
+
+    /// Use one more line if that avoids a last row with only one or two
+    /// cells in it, when the cells go across
+    pub avoid_widows: bool,
+
+    /// A line to put in between groups of rows, to make long grids easier to
+    /// scan
+    pub row_separator: Option<RowSeparator>,
+
+    /// The number of blank lines to put in between rows, for a less dense
+    /// grid
+    pub row_spacing: usize,
+
+    /// Pad the last cell of every row, so that every line of the rows is as
+    /// wide as the grid, for output that is overlaid or compared by column
+    pub pad_last_column: bool,
+
+    /// Text to put at the start of every row, such as `"│ "`, which is part
+    /// of the width
+    ///
+    /// This is not used by the box drawn with the alternate flag.
+    pub row_start: String,
+
+    /// Text to put at the end of every row, such as `" │"`, which is part of
+    /// the width
+    ///
+    /// The rows are padded so that it lines up. This is not used by the box
+    /// drawn with the alternate flag.
+    pub row_end: String,
+
+    /// The function called with the index of every row to get the text to
+    /// put before and after each of its lines, such as escape sequences that
+    /// set and reset a background color to stripe the rows
+    ///
+    /// The text doesn’t count against the width. This is not used by the box
+    /// drawn with the alternate flag.
+    pub row_style: Option<fn(usize) -> (String, String)>,
+
+    /// A row to display after the last row of cells, which does not affect
+    /// the layout
+    pub footer: Option<Footer>,
+
+    /// A line to display above the grid, aligned within its width
+    pub title: Option<Caption>,
+
+    /// A line to display below the grid and its footer, aligned within its
+    /// width
+    pub caption: Option<Caption>,
+
+    /// Text to put at the start of every line, such as a number of spaces to
+    /// indent a nested listing
+    ///
+    /// Its width is taken off the width available for the cells.
+    pub indent: String,
+
+    /// The text to end every line with, such as `"\r\n"` for Windows
+    pub line_terminator: String,
+
+    /// End the last line with the line terminator too, which can be turned
+    /// off to embed the grid in other text
+    pub final_newline: bool,
+}
+
+impl GridOptions {
+    /// Whether any of the options change the contents of cells.
+    fn rewrites_cells(&self) -> bool {
+        self.expand_tabs.is_some()
+            || self.control_characters != ControlCharacters::Keep
+            || self.newlines == Newlines::Escape
+            || self.overflow != Overflow::Extend
+            || self.quoting != Quoting::Literal
+            || self.numbered
+    }
+
+    /// Returns the contents of the cell as changed by the options, or `None`
+    /// if they stay the same.
+    fn rewrite(&self, text: &str) -> Option<String> {
+        let mut rewritten = None;
+
+        if let Some(tab_size) = self.expand_tabs {
+            if text.contains('\t') {
+                rewritten = Some(text::expand_tabs(text, tab_size, |t| self.measure(t)));
+            }
+        }
+
+        if self.newlines == Newlines::Escape {
+            let current = rewritten.as_deref().unwrap_or(text);
+            if current.contains('\n') {
+                rewritten = Some(current.replace('\n', "\\n"));
+            }
+        }
+
+        if self.control_characters != ControlCharacters::Keep {
+            let keep_newlines = self.splits_lines();
+            let current = rewritten.as_deref().unwrap_or(text);
+            if current.contains(|c: char| c.is_ascii_control() && !(keep_newlines && c == '\n')) {
+                rewritten = Some(text::sanitize(
+                    current,
+                    self.control_characters,
+                    keep_newlines,
+                ));
+            }
+        }
+
+        if let Some(quoted) = text::quote(rewritten.as_deref().unwrap_or(text), self.quoting) {
+            rewritten = Some(quoted);
+        }
+
+        let current = rewritten.as_deref().unwrap_or(text);
+        let width = self.available_width();
+        if self.overflow != Overflow::Extend && self.measure_cell(current) > width {
+            let measure = |t: &str| self.measure(t);
+            let lines: Vec<String> = current
+                .split('\n')
+                .map(|line| match &self.overflow {
+                    Overflow::Truncate(ellipsis) => {
+                        text::truncate(line, width, ellipsis, measure).0
+                    }
+                    Overflow::Wrap(indent) => text::wrap(line, width, *indent, measure),
+                    Overflow::Extend => line.to_string(),
+                })
+                .collect();
+            rewritten = Some(lines.join("\n"));
+        }
+
+        rewritten
+    }
+
+    /// The width available for the cells, which is what is left of the width
+    /// after the indentation.
+    fn available_width(&self) -> usize {
+        self.width.saturating_sub(
+            self.measure(&self.indent)
+                + self.measure(&self.row_start)
+                + self.measure(&self.row_end),
+        )
+    }
+
+    /// Whether multi-line cells should be displayed as such.
+    fn splits_lines(&self) -> bool {
+        self.newlines == Newlines::Split || matches!(self.overflow, Overflow::Wrap(_))
+    }
+
+    /// Calculates the width of the contents of a cell, which is the width of
+    /// its widest line if it is split into lines.
+    fn measure_cell(&self, text: &str) -> usize {
+        if self.splits_lines() && text.contains('\n') {
+            text.lines()
+                .map(|line| self.measure(line))
+                .max()
+                .unwrap_or(0)
+        } else {
+            self.measure(text)
+        }
+    }
+
+    fn measure(&self, text: &str) -> usize {
+        match (self.measure, self.ambiguous_width, self.emoji_width) {
+            (Some(measure), _, _) => measure(text),
+            (None, AmbiguousWidth::Narrow, EmojiWidth::Separate) => measure(text),
+            (None, ambiguous_width, emoji_width) => {
+                width::measure(text, ambiguous_width, emoji_width)
+            }
+        }
+    }
+}
+
+impl Default for GridOptions {
+    fn default() -> Self {
+        Self {
+            direction: Direction::LeftToRight,
+            mode: Mode::Grid,
+            filling: Filling::Spaces(2),
+            width: 80,
+            alignment: Alignment::Left,
+            measure: None,
+            ambiguous_width: AmbiguousWidth::Narrow,
+            emoji_width: EmojiWidth::Separate,
+            expand_tabs: None,
+            control_characters: ControlCharacters::Keep,
+            newlines: Newlines::Keep,
+            vertical_alignment: VerticalAlignment::Top,
+            overflow: Overflow::Extend,
+            minimum_spacing: None,
+            quoting: Quoting::Literal,
+            numbered: false,
+            padding: ' ',
+            max_columns: None,
+            columns: Vec::new(),
+            equal_columns: false,
+            tab_stops: None,
+            padding_tabs: None,
+            justify: None,
+            strategy: Strategy::FewestLines,
+            avoid_widows: false,
+            row_separator: None,
+            row_spacing: 0,
+            pad_last_column: false,
+            row_start: String::new(),
+            row_end: String::new(),
+            row_style: None,
+            footer: None,
+            title: None,
+            caption: None,
+            indent: String::new(),
+            line_terminator: "\n".into(),
+            final_newline: true,
+        }
+    }
+}
+
+/// Builder methods, for setting the most common options in a chain instead of
+/// with struct update syntax.
+///
+/// ```
+/// use term_grid::{Direction, Filling, GridOptions};
+///
+/// let options = GridOptions::new()
+///     .width(40)
+///     .top_to_bottom()
+///     .filling(Filling::Text(" | ".into()));
+/// assert_eq!(options.width, 40);
+/// assert_eq!(options.direction, Direction::TopToBottom);
+/// ```
+impl GridOptions {
+    /// Creates the default options, which are the same as those of
+    /// [`GridOptions::default`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the width to fill with the grid.
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Sets the direction that the cells are written in.
+    pub fn direction(mut self, direction: Direction) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    /// Writes the cells across, see [`Direction::LeftToRight`].
+    pub fn left_to_right(self) -> Self {
+        self.direction(Direction::LeftToRight)
+    }
+
+    /// Writes the cells downwards, see [`Direction::TopToBottom`].
+    pub fn top_to_bottom(self) -> Self {
+        self.direction(Direction::TopToBottom)
+    }
+
+    /// Sets whether the cells are laid out in columns or written as a stream.
+    pub fn mode(mut self, mode: Mode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Sets the string to put in between each column of cells.
+    pub fn filling(mut self, filling: Filling) -> Self {
+        self.filling = filling;
+        self
+    }
+
+    /// Puts the given number of spaces in between each column of cells.
+    pub fn spacing(self, spaces: usize) -> Self {
+        self.filling(Filling::Spaces(spaces))
+    }
+
+    /// Sets the alignment of cells that do not specify their own.
+    pub fn alignment(mut self, alignment: Alignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    /// Sets the string to put at the start of every line.
+    pub fn indent(mut self, indent: impl Into<String>) -> Self {
+        self.indent = indent.into();
+        self
+    }
+}
+
+/// The reasons why cells cannot be laid out in a grid, returned by
+/// [`Grid::try_new`].
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum LayoutError {
+    /// The cell at the given index is wider than the grid may be, so it can
+    /// only be displayed in a single column that is too wide.
+    CellTooWide {
+        /// The index of the first cell that is too wide
+        index: usize,
+
+        /// The width of that cell
+        width: usize,
+    },
+
+    /// There are cells to lay out, but no width left for them after the
+    /// indentation and the start and end of the rows.
+    NoWidth,
+
+    /// A [`Filling::PerColumn`] or [`Filling::Cycle`] has no fillings in it.
+    EmptyFilling,
+
+    /// A [`Filling::Text`] has a line break in it, which would break up the
+    /// rows.
+    NewlineInFilling,
+}
+
+impl fmt::Display for LayoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LayoutError::CellTooWide { index, width } => {
+                write!(
+                    f,
+                    "cell {index} is too wide to fit in the grid ({width} columns)"
+                )
+            }
+            LayoutError::NoWidth => write!(f, "there is no width left for the cells"),
+            LayoutError::EmptyFilling => write!(f, "the list of fillings is empty"),
+            LayoutError::NewlineInFilling => write!(f, "the filling has a line break in it"),
+        }
+    }
+}
+
+impl std::error::Error for LayoutError {}
+
+/// How well the cells of a grid are packed into its columns, returned by
+/// [`Grid::layout_report`].
+#[derive(PartialEq, Debug, Clone)]
+pub struct LayoutReport {
+    /// The number of places in the grid without a cell, such as at the end of
+    /// the last row or column
+    pub empty_slots: usize,
+
+    /// The number of blank characters in the columns around the cells that
+    /// are narrower than their column, not counting the empty slots and the
+    /// separators between columns
+    pub padding: usize,
+
+    /// How much of each column is taken up by cells, from 0 to 1
+    pub column_fill: Vec<f64>,
+
+    /// Whether the cells did not fit in the width, so they were put in a
+    /// single column that is too wide instead
+    pub single_column_fallback: bool,
+}
+
+/// Where the columns of a grid are, returned by [`Grid::layout`], for drawing
+/// the cells some other way while keeping the same columns.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Layout {
+    /// The width of each column, which does not include the separators
+    pub column_widths: Vec<usize>,
+
+    /// The number of rows, see [`Grid::row_count`]
+    pub row_count: usize,
+
+    /// The width of the separator after each column but the last
+    pub gap_widths: Vec<usize>,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+struct Dimensions {
+    /// The number of lines in the grid.
+    num_lines: usize,
+
+    /// The width of each column in the grid. The length of this vector serves
+    /// as the number of columns.
+    widths: Vec<usize>,
+
+    /// The widest integer and fractional parts of the decimal-aligned cells
+    /// in each column. This is empty if there are no such cells.
+    decimals: Vec<(usize, usize)>,
+
+    /// The width of the separator between each pair of columns, which can be
+    /// less than the width of the filling if the spacing was reduced.
+    separator_width: usize,
+
+    /// The width of the separator after each column but the last, if the
+    /// filling differs between gaps. This is empty otherwise.
+    gap_widths: Vec<usize>,
+
+    /// Where the cells are when some of them span several columns.
+    spans: Option<SpanLayout>,
+
+    /// Whether the cells did not fit in the width, so they were put in a
+    /// single column instead.
+    fallback: bool,
+}
+
+/// The places of the cells of a grid in which some cells span several
+/// columns, see [`GridCell::span`].
+#[derive(PartialEq, Eq, Debug, Clone)]
+struct SpanLayout {
+    /// The row, first column and number of columns of each visible cell.
+    places: Vec<(usize, usize, usize)>,
+
+    /// The visible cell that starts in each column of each row, row by row.
+    starts: Vec<Option<usize>>,
+}
+
+impl Dimensions {
+    /// Widens every column but the last so that the total width becomes the
+    /// given width.
+    fn justify(&mut self, justify: Justify, width: usize) {
+        let total_width = self.total_width();
+        let num_gaps = self.widths.len().saturating_sub(1);
+        if num_gaps == 0 || total_width >= width {
+            return;
+        }
+
+        let leftover = width - total_width;
+        let gaps = &mut self.widths[..num_gaps];
+        match justify {
+            Justify::Even => {
+                for (i, column_width) in gaps.iter_mut().enumerate() {
+                    *column_width += leftover / num_gaps + usize::from(i < leftover % num_gaps);
+                }
+            }
+            Justify::Proportional => {
+                let gap_total = gaps.iter().sum::<usize>().max(1);
+                let mut given = 0;
+                for column_width in gaps.iter_mut() {
+                    let extra = leftover * *column_width / gap_total;
+                    *column_width += extra;
+                    given += extra;
+                }
+                // Hand out what's left from rounding down from the left.
+                for column_width in gaps.iter_mut().take(leftover - given) {
+                    *column_width += 1;
+                }
+            }
+        }
+    }
+
+    fn total_width(&self) -> usize {
+        if self.widths.is_empty() {
+            0
+        } else {
+            let values = self.widths.iter().sum::<usize>();
+            let separators = if self.gap_widths.is_empty() {
+                self.separator_width * (self.widths.len() - 1)
+            } else {
+                self.gap_widths.iter().sum()
+            };
+            values + separators
+        }
+    }
+
+    /// The width of the separator after column `x`.
+    fn gap_width(&self, x: usize) -> usize {
+        self.gap_widths
+            .get(x)
+            .copied()
+            .unwrap_or(self.separator_width)
+    }
+
+    /// The width of the given number of columns starting with column `x`,
+    /// including the separators in between them.
+    fn span_width(&self, x: usize, span: usize) -> usize {
+        (x..x + span).map(|x| self.widths[x]).sum::<usize>()
+            + (x..x + span - 1).map(|x| self.gap_width(x)).sum::<usize>()
+    }
+}
+
+/// The text of a cell that is not borrowed from the cell itself.
+#[derive(PartialEq, Eq, Debug, Clone)]
+enum OwnedContents {
+    /// The cell returned owned contents, such as the lossy conversion of a
+    /// string that is not valid UTF-8.
+    Converted(String),
+
+    /// The contents were changed by the grid options.
+    Rewritten(String),
+}
+
+/// A destination that a grid can be written to.
+trait Output {
+    type Error;
+
+    fn write_str(&mut self, s: &str) -> Result<(), Self::Error>;
+
+    /// Writes (a line of) the contents of a cell, or its exact bytes if it
+    /// has them and this output supports them.
+    fn write_cell(&mut self, contents: &str, _bytes: Option<&[u8]>) -> Result<(), Self::Error> {
+        self.write_str(contents)
+    }
+
+    /// Writes a cell whose contents were not stored by the grid, see
+    /// [`GridCell::write`].
+    fn write_grid_cell<T: GridCell>(&mut self, cell: &T) -> Result<(), Self::Error>;
+}
+
+impl Output for fmt::Formatter<'_> {
+    type Error = fmt::Error;
+
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        fmt::Write::write_str(self, s)
+    }
+
+    fn write_grid_cell<T: GridCell>(&mut self, cell: &T) -> fmt::Result {
+        cell.write(self)
+    }
+}
+
+/// Output that puts the indentation at the start of every line that is not
+/// blank and ends lines with the line terminator, see [`GridOptions::indent`]
+/// and [`GridOptions::line_terminator`].
+///
+/// The terminator is only written once the next line starts, so that it can
+/// be left out after the last line.
+struct LineOutput<'a, O> {
+    inner: &'a mut O,
+    indent: &'a str,
+    terminator: &'a str,
+    line_start: bool,
+    pending_terminator: bool,
+}
+
+impl<O: Output> LineOutput<'_, O> {
+    fn end_line(&mut self) -> Result<(), O::Error> {
+        if self.pending_terminator {
+            self.pending_terminator = false;
+            self.inner.write_str(self.terminator)?;
+        }
+        Ok(())
+    }
+
+    fn start_line(&mut self) -> Result<(), O::Error> {
+        self.end_line()?;
+        if self.line_start {
+            self.line_start = false;
+            self.inner.write_str(self.indent)?;
+        }
+        Ok(())
+    }
+}
+
+impl<O: Output> Output for LineOutput<'_, O> {
+    type Error = O::Error;
+
+    fn write_str(&mut self, s: &str) -> Result<(), O::Error> {
+        for line in s.split_inclusive('\n') {
+            let (text, ends_line) = match line.strip_suffix('\n') {
+                Some(text) => (text, true),
+                None => (line, false),
+            };
+            if !text.is_empty() {
+                self.start_line()?;
+                self.inner.write_str(text)?;
+            }
+            if ends_line {
+                self.end_line()?;
+                self.pending_terminator = true;
+                self.line_start = true;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_cell(&mut self, contents: &str, bytes: Option<&[u8]>) -> Result<(), O::Error> {
+        self.start_line()?;
+        self.inner.write_cell(contents, bytes)
+    }
+
+    fn write_grid_cell<T: GridCell>(&mut self, cell: &T) -> Result<(), O::Error> {
+        self.start_line()?;
+        self.inner.write_grid_cell(cell)
+    }
+}
+
+/// Output to a [`fmt::Write`], such as a string.
+struct FmtOutput<'a, W>(&'a mut W);
+
+impl<W: fmt::Write> Output for FmtOutput<'_, W> {
+    type Error = fmt::Error;
+
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0.write_str(s)
+    }
+
+    fn write_grid_cell<T: GridCell>(&mut self, cell: &T) -> fmt::Result {
+        cell.write(self.0)
+    }
+}
+
+/// Output to an [`io::Write`], which supports writing cells byte-for-byte.
+struct IoOutput<'a, W: ?Sized>(&'a mut W);
+
+impl<W: io::Write + ?Sized> Output for IoOutput<'_, W> {
+    type Error = io::Error;
+
+    fn write_str(&mut self, s: &str) -> io::Result<()> {
+        self.0.write_all(s.as_bytes())
+    }
+
+    fn write_cell(&mut self, contents: &str, bytes: Option<&[u8]>) -> io::Result<()> {
+        self.0.write_all(bytes.unwrap_or(contents.as_bytes()))
+    }
+
+    fn write_grid_cell<T: GridCell>(&mut self, cell: &T) -> io::Result<()> {
+        if let Some(bytes) = cell.bytes() {
+            return self.0.write_all(bytes);
+        }
+
+        // Keep the I/O error around, as `fmt::Write` can't return it.
+        struct Adapter<'a, W: ?Sized> {
+            inner: &'a mut W,
+            error: Option<io::Error>,
+        }
+
+        impl<W: io::Write + ?Sized> fmt::Write for Adapter<'_, W> {
+            fn write_str(&mut self, s: &str) -> fmt::Result {
+                self.inner.write_all(s.as_bytes()).map_err(|e| {
+                    self.error = Some(e);
+                    fmt::Error
+                })
+            }
+        }
+
+        let mut adapter = Adapter {
+            inner: &mut *self.0,
+            error: None,
+        };
+        cell.write(&mut adapter).map_err(|_| {
+            adapter
+                .error
+                .unwrap_or_else(|| io::Error::new(io::ErrorKind::Other, "formatter error"))
+        })
+    }
+}
+
+/// Everything needed to format the cells with the grid options.
+#[derive(Debug, Clone)]
+pub struct Grid<T: GridCell> {
+    options: GridOptions,
+    cells: Vec<T>,
+    visible: Option<Vec<usize>>,
+    owned_contents: Vec<Option<OwnedContents>>,
+    multiline: bool,
+    widths: Vec<usize>,
+    min_widths: Vec<usize>,
+    spans: Vec<usize>,
+    placeholders: Vec<bool>,
+    integer_widths: Vec<Option<usize>>,
+    widest_cell_width: usize,
+    separator_width: usize,
+    dimensions: OnceLock<Dimensions>,
+}
+
+impl<T: GridCell> Grid<T> {
+    /// Creates a new grid view with the given cells and options
+    ///
+    /// The cells are measured right away, but they are only laid out when
+    /// the grid is first displayed or measured, so the layout is not worked
+    /// out for nothing if the cells are then laid out in some other way,
+    /// such as with [`Grid::fit_into_columns`].
+    pub fn new(cells: Vec<T>, options: GridOptions) -> Self {
+        let separator_width = options.filling.width(|t| options.measure(t));
+        let mut grid = Self {
+            options,
+            cells,
+            visible: None,
+            owned_contents: Vec::new(),
+            multiline: false,
+            widths: Vec::new(),
+            min_widths: Vec::new(),
+            spans: Vec::new(),
+            placeholders: Vec::new(),
+            integer_widths: Vec::new(),
+            widest_cell_width: 0,
+            separator_width,
+            dimensions: OnceLock::new(),
+        };
+        grid.measure_cells();
+        grid
+    }
+
+    /// Creates an empty grid with room for the given number of cells, which
+    /// can then be added without reallocating.
+    ///
+    /// ```
+    /// use term_grid::{Grid, GridOptions};
+    ///
+    /// let mut grid = Grid::with_capacity(1000, GridOptions::default());
+    /// let capacity = grid.capacity();
+    /// assert!(capacity >= 1000);
+    /// for i in 0..1000 {
+    ///     grid.push(i.to_string());
+    /// }
+    /// assert_eq!(grid.capacity(), capacity);
+    /// ```
+    pub fn with_capacity(capacity: usize, options: GridOptions) -> Self {
+        let mut grid = Self::new(Vec::with_capacity(capacity), options);
+        grid.widths.reserve(capacity);
+        grid
+    }
+
+    /// Makes room for at least the given number of cells more, which can
+    /// then be added without reallocating.
+    pub fn reserve(&mut self, additional: usize) {
+        self.cells.reserve(additional);
+        self.widths.reserve(additional);
+        for values in [&mut self.min_widths, &mut self.spans] {
+            if !values.is_empty() {
+                values.reserve(additional);
+            }
+        }
+        if !self.owned_contents.is_empty() {
+            self.owned_contents.reserve(additional);
+        }
+        if !self.integer_widths.is_empty() {
+            self.integer_widths.reserve(additional);
+        }
+        if let Some(visible) = &mut self.visible {
+            visible.reserve(additional);
+        }
+    }
+
+    /// The number of cells the grid can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.cells.capacity()
+    }
+
+    /// Adds a cell to the end of the grid.
+    ///
+    /// The cells are laid out again when the grid is next displayed or
+    /// measured, or when [`Grid::fit_into_width`] is called, so cells can be
+    /// added one at a time without the cost of laying them out every time.
+    ///
+    /// ```
+    /// use term_grid::{Grid, GridOptions};
+    ///
+    /// let mut grid = Grid::new(Vec::new(), GridOptions::default());
+    /// for cell in ["one", "two", "three"] {
+    ///     grid.add(cell);
+    /// }
+    /// assert_eq!(grid.to_string(), "one  two  three\n");
+    /// ```
+    pub fn add(&mut self, cell: T) {
+        self.push(cell);
+    }
+
+    /// Adds a cell to the end of the grid, like [`Grid::add`] does.
+    pub fn push(&mut self, cell: T) {
+        let start = self.cells.len();
+        self.cells.push(cell);
+        self.cells_added(start);
+    }
+
+    /// Inserts a cell at the given index, moving the cells after it along.
+    ///
+    /// Only the new cell is measured, unless the cells are
+    /// [numbered](GridOptions::numbered), and the cells are laid out again
+    /// when the grid is next displayed or measured.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is greater than the number of cells.
+    ///
+    /// ```
+    /// use term_grid::{Grid, GridOptions};
+    ///
+    /// let mut grid = Grid::new(vec!["one", "three"], GridOptions::default());
+    /// grid.insert(1, "two");
+    /// assert_eq!(grid.to_string(), "one  two  three\n");
+    /// ```
+    pub fn insert(&mut self, index: usize, cell: T) {
+        let num_cells = self.cells.len();
+        self.cells.insert(index, cell);
+        let measurement = self.measure(index);
+        self.store(index, measurement, true);
+        if !self.placeholders.is_empty() {
+            self.placeholders.insert(index, false);
+        }
+        self.numbers_moved(num_cells, index + 1);
+        self.dimensions = OnceLock::new();
+    }
+
+    /// Removes the cell at the given index and returns it, moving the cells
+    /// after it back.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no cell at the index.
+    ///
+    /// ```
+    /// use term_grid::{Grid, GridOptions};
+    ///
+    /// let mut grid = Grid::new(vec!["one", "two", "three"], GridOptions::default());
+    /// assert_eq!(grid.remove(1), "two");
+    /// assert_eq!(grid.to_string(), "one  three\n");
+    /// ```
+    pub fn remove(&mut self, index: usize) -> T {
+        let num_cells = self.cells.len();
+        let old_width = self.laid_out_width(index);
+        let cell = self.cells.remove(index);
+
+        self.widths.remove(index);
+        for values in [&mut self.min_widths, &mut self.spans] {
+            if !values.is_empty() {
+                values.remove(index);
+            }
+        }
+        if !self.owned_contents.is_empty() {
+            self.owned_contents.remove(index);
+        }
+        if !self.integer_widths.is_empty() {
+            self.integer_widths.remove(index);
+        }
+        if !self.placeholders.is_empty() {
+            self.placeholders.remove(index);
+        }
+        if let Some(visible) = &mut self.visible {
+            if let Ok(slot) = visible.binary_search(&index) {
+                visible.remove(slot);
+            }
+            for i in visible.iter_mut().filter(|i| **i > index) {
+                *i -= 1;
+            }
+        }
+        if old_width == Some(self.widest_cell_width) {
+            self.widest_cell_width = self.widest_laid_out_width();
+        }
+
+        self.numbers_moved(num_cells, index);
+        self.dimensions = OnceLock::new();
+        cell
+    }
+
+    /// Keeps only the cells for which the given function returns `true`, in
+    /// the same order. The cells that are kept are not measured again,
+    /// unless they are [numbered](GridOptions::numbered).
+    ///
+    /// ```
+    /// use term_grid::{Grid, GridOptions};
+    ///
+    /// let mut grid = Grid::new(vec!["apple", "banana", "avocado"], GridOptions::default());
+    /// grid.retain(|cell| cell.starts_with('a'));
+    /// assert_eq!(grid.to_string(), "apple  avocado\n");
+    /// ```
+    pub fn retain(&mut self, mut f: impl FnMut(&T) -> bool) {
+        let keep: Vec<bool> = self.cells.iter().map(&mut f).collect();
+        if keep.iter().all(|&kept| kept) {
+            return;
+        }
+        let num_cells = self.cells.len();
+
+        retain_kept(&mut self.cells, &keep);
+        retain_kept(&mut self.widths, &keep);
+        retain_kept(&mut self.min_widths, &keep);
+        retain_kept(&mut self.spans, &keep);
+        retain_kept(&mut self.owned_contents, &keep);
+        retain_kept(&mut self.integer_widths, &keep);
+        retain_kept(&mut self.placeholders, &keep);
+        if let Some(visible) = &mut self.visible {
+            // The new index of each cell is the number of cells kept before it.
+            let new_indices: Vec<usize> = keep
+                .iter()
+                .scan(0, |num_kept, &kept| {
+                    *num_kept += usize::from(kept);
+                    Some(*num_kept - usize::from(kept))
+                })
+                .collect();
+            visible.retain(|&index| keep[index]);
+            for index in visible.iter_mut() {
+                *index = new_indices[*index];
+            }
+        }
+        self.widest_cell_width = self.widest_laid_out_width();
+
+        self.numbers_moved(num_cells, 0);
+        self.dimensions = OnceLock::new();
+    }
+
+    /// Sorts the cells, see [`Grid::sort_cells_by`].
+    pub fn sort_cells(&mut self)
+    where
+        T: Ord,
+    {
+        self.sort_cells_by(T::cmp);
+    }
+
+    /// Sorts the cells by the keys that the given function returns for
+    /// them, see [`Grid::sort_cells_by`].
+    ///
+    /// ```
+    /// use term_grid::{Grid, GridOptions};
+    ///
+    /// let mut grid = Grid::new(vec!["ccc", "a", "bb"], GridOptions::default());
+    /// grid.sort_cells_by_key(|cell| cell.len());
+    /// assert_eq!(grid.to_string(), "a  bb  ccc\n");
+    /// ```
+    pub fn sort_cells_by_key<K: Ord>(&mut self, mut key: impl FnMut(&T) -> K) {
+        self.sort_cells_by(|a, b| key(a).cmp(&key(b)));
+    }
+
+    /// Sorts the cells with the given comparison function, keeping cells
+    /// that compare equal in the same order. The cells are not measured
+    /// again, unless they are [numbered](GridOptions::numbered), but they are
+    /// laid out again when the grid is next displayed or measured.
+    pub fn sort_cells_by(&mut self, mut compare: impl FnMut(&T, &T) -> Ordering) {
+        let mut order: Vec<usize> = (0..self.cells.len()).collect();
+        order.sort_by(|&a, &b| compare(&self.cells[a], &self.cells[b]));
+
+        let mut cells: Vec<Option<T>> = std::mem::take(&mut self.cells)
+            .into_iter()
+            .map(Some)
+            .collect();
+        self.cells = order
+            .iter()
+            .map(|&index| cells[index].take().expect("every cell is taken once"))
+            .collect();
+        permute(&mut self.widths, &order);
+        permute(&mut self.min_widths, &order);
+        permute(&mut self.spans, &order);
+        permute(&mut self.owned_contents, &order);
+        permute(&mut self.integer_widths, &order);
+        permute(&mut self.placeholders, &order);
+        if let Some(visible) = &mut self.visible {
+            let mut is_visible = vec![false; order.len()];
+            for &index in visible.iter() {
+                is_visible[index] = true;
+            }
+            *visible = (0..order.len()).filter(|&i| is_visible[order[i]]).collect();
+        }
+
+        if self.options.numbered {
+            self.measure_again(0..self.cells.len());
+        }
+        self.dimensions = OnceLock::new();
+    }
+
+    /// Replaces the cell at the given index with another one, and returns
+    /// the one that was there.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no cell at the index.
+    ///
+    /// ```
+    /// use term_grid::{Grid, GridOptions};
+    ///
+    /// let mut grid = Grid::new(vec!["one", "two", "three"], GridOptions::default());
+    /// assert_eq!(grid.replace(1, "2"), "two");
+    /// assert_eq!(grid.to_string(), "one  2  three\n");
+    /// ```
+    pub fn replace(&mut self, index: usize, cell: T) -> T {
+        let old = std::mem::replace(&mut self.cells[index], cell);
+        let measurement = self.measure(index);
+        self.store(index, measurement, false);
+        self.dimensions = OnceLock::new();
+        old
+    }
+
+    /// Numbers the cells from the given index onwards again, or all of them
+    /// if the numbers got wider or narrower, after the number of cells
+    /// changed from `num_cells`.
+    fn numbers_moved(&mut self, num_cells: usize, start: usize) {
+        if self.renumbered(num_cells) {
+            self.measure_again(0..self.cells.len());
+        } else if self.options.numbered {
+            self.measure_again(start..self.cells.len());
+        }
+    }
+
+    /// Changes the options of the grid, after which the cells are measured
+    /// and laid out again.
+    ///
+    /// ```
+    /// use term_grid::{Grid, GridOptions};
+    ///
+    /// let grid: Grid<_> = ["one", "two", "three"].into_iter().collect();
+    /// let grid = grid.with_options(GridOptions::new().width(10));
+    /// assert_eq!(grid.to_string(), "one\ntwo\nthree\n");
+    /// ```
+    pub fn with_options(mut self, options: GridOptions) -> Self {
+        self.separator_width = options.filling.width(|t| options.measure(t));
+        self.options = options;
+        self.measure_cells();
+        self.dimensions = OnceLock::new();
+        self
+    }
+
+    /// Returns the cells of the grid, in the order they were given.
+    pub fn cells(&self) -> &[T] {
+        &self.cells
+    }
+
+    /// Returns the width of each cell, as the grid measured it.
+    ///
+    /// ```
+    /// use term_grid::{Grid, GridOptions};
+    ///
+    /// let grid = Grid::new(vec!["one", "three"], GridOptions::default());
+    /// assert_eq!(grid.cell_widths(), [3, 5]);
+    /// ```
+    pub fn cell_widths(&self) -> &[usize] {
+        &self.widths
+    }
+
+    /// Takes the cells back out of the grid, in the order they were given.
+    ///
+    /// ```
+    /// use term_grid::{Grid, GridOptions};
+    ///
+    /// let grid = Grid::new(vec![String::from("one"), String::from("two")], GridOptions::default());
+    /// assert_eq!(grid.to_string(), "one  two\n");
+    /// assert_eq!(grid.into_cells(), ["one", "two"]);
+    /// ```
+    pub fn into_cells(self) -> Vec<T> {
+        self.cells
+    }
+
+    /// Makes a grid of what the given function returns for each cell, with
+    /// the same options. The new cells are measured and laid out again.
+    ///
+    /// ```
+    /// use term_grid::{Grid, GridOptions};
+    ///
+    /// let grid = Grid::new(vec!["one", "two"], GridOptions::default());
+    /// let grid = grid.map(|cell| format!("[{cell}]"));
+    /// assert_eq!(grid.to_string(), "[one]  [two]\n");
+    /// ```
+    pub fn map<U: GridCell>(self, f: impl FnMut(T) -> U) -> Grid<U> {
+        Grid::new(self.cells.into_iter().map(f).collect(), self.options)
+    }
+
+    /// Makes a grid of what the given function returns for each cell, like
+    /// [`Grid::map`], for a function that keeps the width of every cell and
+    /// everything else about its layout, such as one that only adds colours.
+    /// The cells are then not measured or laid out again.
+    ///
+    /// ```
+    /// use term_grid::{Grid, GridOptions};
+    ///
+    /// let grid = Grid::new(vec!["one", "two"], GridOptions::default());
+    /// let grid = grid.map_keeping_widths(|cell| format!("\x1b[1m{cell}\x1b[0m"));
+    /// assert_eq!(grid.to_string(), "\x1b[1mone\x1b[0m  \x1b[1mtwo\x1b[0m\n");
+    /// ```
+    pub fn map_keeping_widths<U: GridCell>(self, f: impl FnMut(T) -> U) -> Grid<U> {
+        let mut grid = Grid {
+            options: self.options,
+            cells: self.cells.into_iter().map(f).collect(),
+            visible: self.visible,
+            owned_contents: Vec::new(),
+            multiline: self.multiline,
+            widths: self.widths,
+            min_widths: self.min_widths,
+            spans: self.spans,
+            placeholders: self.placeholders,
+            integer_widths: self.integer_widths,
+            widest_cell_width: self.widest_cell_width,
+            separator_width: self.separator_width,
+            dimensions: self.dimensions,
+        };
+        // The new cells only have to be rewritten, not measured.
+        for index in 0..grid.cells.len() {
+            let owned = grid.own_contents(index);
+            put(&mut grid.owned_contents, index, owned, None, (index, true));
+        }
+        grid
+    }
+
+    /// Lays the cells out within the given width instead of the one in the
+    /// options.
+    ///
+    /// ```
+    /// use term_grid::{Grid, GridOptions};
+    ///
+    /// let mut grid = Grid::new(vec!["one", "two", "three"], GridOptions::default());
+    /// grid.fit_into_width(11);
+    /// assert_eq!(grid.to_string(), "one    two\nthree  \n");
+    /// ```
+    pub fn fit_into_width(&mut self, width: usize) {
+        self.set_width(width);
+        self.dimensions = OnceLock::from(self.lay_out());
+    }
+
+    /// Changes the width to fill with the grid, such as when the terminal is
+    /// resized. The cells are laid out again when the grid is next displayed
+    /// or measured, using the widths they were measured with, unless they are
+    /// cut to fit the width with [`GridOptions::overflow`].
+    ///
+    /// ```
+    /// use term_grid::{Grid, GridOptions};
+    ///
+    /// let mut grid = Grid::new(vec!["one", "two", "three"], GridOptions::default());
+    /// grid.set_width(11);
+    /// assert_eq!(grid.row_count(), 2);
+    /// ```
+    pub fn set_width(&mut self, width: usize) {
+        self.options.width = width;
+        if self.options.overflow != Overflow::Extend {
+            self.measure_cells();
+        }
+        self.dimensions = OnceLock::new();
+    }
+
+    /// Measures every cell again, such as after the options have changed.
+    fn measure_cells(&mut self) {
+        self.visible = None;
+        self.owned_contents.clear();
+        self.multiline = false;
+        self.widths.clear();
+        self.min_widths.clear();
+        self.spans.clear();
+        self.integer_widths.clear();
+        self.widest_cell_width = 0;
+        for index in 0..self.cells.len() {
+            let measurement = self.measure(index);
+            self.store(index, measurement, true);
+        }
+        if !self.placeholders.is_empty() {
+            self.placeholders.resize(self.cells.len(), false);
+        }
+    }
+
+    /// Measures the cells from the given index onwards, which were just
+    /// added, and leaves laying them out for later.
+    fn cells_added(&mut self, start: usize) {
+        if self.renumbered(start) {
+            self.measure_again(0..start);
+        }
+        for index in start..self.cells.len() {
+            let measurement = self.measure(index);
+            self.store(index, measurement, true);
+            if !self.placeholders.is_empty() {
+                self.placeholders.insert(index, false);
+            }
+        }
+        self.dimensions = OnceLock::new();
+    }
+
+    /// Whether the cells have to be numbered again now that there are more
+    /// or fewer of them than the given number, because the numbers of all
+    /// the cells are as wide as the last one.
+    fn renumbered(&self, num_cells: usize) -> bool {
+        self.options.numbered && self.cells.len().to_string().len() != num_cells.to_string().len()
+    }
+
+    /// Measures the cells in the given range again.
+    fn measure_again(&mut self, range: std::ops::Range<usize>) {
+        for index in range {
+            let measurement = self.measure(index);
+            self.store(index, measurement, false);
+        }
+    }
+
+    /// The contents of the cell at the given index that the grid has to
+    /// keep, because they were changed by the options or the cell did not
+    /// lend them out.
+    fn own_contents(&self, index: usize) -> Option<OwnedContents> {
+        let options = &self.options;
+        let cell = &self.cells[index];
+        let rewrites_cells = options.rewrites_cells();
+        let decimal = matches!(
+            cell.alignment().unwrap_or(options.alignment),
+            Alignment::Decimal(_)
+        );
+
+        // Cells that know their width and are displayed as they are don't
+        // need their contents until they are written.
+        if cell.width().is_some() && !rewrites_cells && !options.splits_lines() && !decimal {
+            return None;
+        }
+        let contents = cell.contents();
+        let mut rewritten = if rewrites_cells {
+            options.rewrite(&contents)
+        } else {
+            None
+        };
+        if options.numbered {
+            let number_width = self.cells.len().to_string().len();
+            let text = rewritten.as_deref().unwrap_or(&contents);
+            rewritten = Some(format!("{:>number_width$} {text}", index + 1));
+        }
+        match (rewritten, contents) {
+            (Some(text), _) => Some(OwnedContents::Rewritten(text)),
+            (None, Cow::Owned(text)) => Some(OwnedContents::Converted(text)),
+            (None, Cow::Borrowed(_)) => None,
+        }
+    }
+
+    /// Measures the cell at the given index.
+    fn measure(&self, index: usize) -> Measurement {
+        let options = &self.options;
+        let cell = &self.cells[index];
+        let alignment = cell.alignment().unwrap_or(options.alignment);
+        let owned = self.own_contents(index);
+        let contents = || match &owned {
+            Some(OwnedContents::Converted(text) | OwnedContents::Rewritten(text)) => {
+                Cow::Borrowed(text.as_str())
+            }
+            None => cell.contents(),
+        };
+
+        let width = match &owned {
+            Some(OwnedContents::Rewritten(text)) => options.measure_cell(text),
+            _ => cell
+                .width()
+                .unwrap_or_else(|| options.measure_cell(&contents())),
+        };
+        let across = matches!(
+            options.direction,
+            Direction::LeftToRight | Direction::RightToLeft
+        );
+        let multiline = options.splits_lines() && contents().contains('\n');
+        let integer_width = match alignment {
+            Alignment::Decimal(separator) => {
+                let contents = contents();
+                let measure = |line| integer_width(line, separator, |t| options.measure(t));
+                let integer_width = if multiline {
+                    contents.split('\n').map(measure).max().unwrap_or(0)
+                } else {
+                    measure(&contents)
+                };
+                // A cell can say it is narrower than its text.
+                Some(integer_width.min(width))
+            }
+            _ => None,
+        };
+
+        Measurement {
+            owned,
+            width,
+            min_width: cell.min_width().unwrap_or(0),
+            span: if across { cell.span().max(1) } else { 1 },
+            integer_width,
+            hidden: cell.hidden(),
+            multiline,
+        }
+    }
+
+    /// Keeps the measurement of the cell at the given index, which is either
+    /// a new cell or one that replaces the cell that was there.
+    fn store(&mut self, index: usize, measurement: Measurement, insert: bool) {
+        let Measurement {
+            owned,
+            width,
+            min_width,
+            span,
+            integer_width,
+            hidden,
+            multiline,
+        } = measurement;
+        let num_stored = self.widths.len();
+        let old_width = (!insert).then(|| self.laid_out_width(index)).flatten();
+
+        let place = (num_stored, insert);
+        put(&mut self.owned_contents, index, owned, None, place);
+        put(&mut self.min_widths, index, min_width, 0, place);
+        put(&mut self.spans, index, span, 1, place);
+        put(&mut self.integer_widths, index, integer_width, None, place);
+        if insert {
+            self.widths.insert(index, width);
+        } else {
+            self.widths[index] = width;
+        }
+        self.multiline |= multiline;
+
+        // Only keep track of which cells are laid out if some are hidden.
+        if hidden && self.visible.is_none() {
+            let num_cells = num_stored + usize::from(insert);
+            self.visible = Some((0..num_cells).filter(|&i| i != index).collect());
+        } else if let Some(visible) = &mut self.visible {
+            if insert && index == num_stored {
+                // A cell added at the end comes after all the others.
+                if !hidden {
+                    visible.push(index);
+                }
+            } else {
+                if insert {
+                    for i in visible.iter_mut().filter(|i| **i >= index) {
+                        *i += 1;
+                    }
+                }
+                match (visible.binary_search(&index), hidden) {
+                    (Ok(slot), true) => {
+                        visible.remove(slot);
+                    }
+                    (Err(slot), false) => visible.insert(slot, index),
+                    _ => {}
+                }
+            }
+        }
+
+        if old_width == Some(self.widest_cell_width) {
+            self.widest_cell_width = self.widest_laid_out_width();
+        } else if !hidden {
+            self.widest_cell_width = self.widest_cell_width.max(width.max(min_width));
+        }
+    }
+
+    /// The width that the cell at the given index takes up when it is laid
+    /// out, or `None` if it is hidden.
+    fn laid_out_width(&self, index: usize) -> Option<usize> {
+        self.slot(index)?;
+        let min_width = self.min_widths.get(index).copied().unwrap_or(0);
+        Some(self.widths[index].max(min_width))
+    }
+
+    /// The width of the widest cell that is laid out.
+    fn widest_laid_out_width(&self) -> usize {
+        (0..self.widths.len())
+            .filter_map(|index| self.laid_out_width(index))
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Works out the dimensions of the grid for the width in the options.
+    fn lay_out(&self) -> Dimensions {
+        if self.options.mode == Mode::SingleColumn {
+            return self.column_widths(self.visible_count(), 1);
+        }
+
+        let width = self.options.available_width();
+        let mut dimensions = self.width_dimensions(width).unwrap_or_else(|| Dimensions {
+            fallback: true,
+            ..self.column_widths(self.visible_count(), 1)
+        });
+        if let Some(justify) = self.options.justify {
+            dimensions.justify(justify, width.saturating_sub(1));
+        }
+        dimensions
+    }
+
+    /// The dimensions of the grid, which are worked out again if cells were
+    /// added since they last were.
+    fn dimensions(&self) -> &Dimensions {
+        self.dimensions.get_or_init(|| self.lay_out())
+    }
+
+    /// Creates a new grid view with the given cells in a single column, with
+    /// the default options otherwise.
+    ///
+    /// ```
+    /// use term_grid::Grid;
+    ///
+    /// let grid = Grid::single_column(vec!["one", "two"]);
+    /// assert_eq!(grid.to_string(), "one\ntwo\n");
+    /// ```
+    pub fn single_column(cells: Vec<T>) -> Self {
+        let options = GridOptions {
+            mode: Mode::SingleColumn,
+            ..GridOptions::default()
+        };
+        Self::new(cells, options)
+    }
+
+    /// Creates a new grid view with the given cells and options, or returns
+    /// an error if the cells cannot be laid out within the width or the
+    /// options do not make sense.
+    ///
+    /// [`Grid::new`] falls back to displaying the cells in a single column
+    /// that is wider than the given width in that case. With
+    /// [`Mode::SingleColumn`], cells can be wider than the width.
+    ///
+    /// ```
+    /// use term_grid::{Filling, Grid, GridOptions, LayoutError};
+    ///
+    /// let options = GridOptions::new().filling(Filling::Text(" |\n".into()));
+    /// let result = Grid::try_new(vec!["one", "two"], options);
+    /// assert_eq!(result.unwrap_err(), LayoutError::NewlineInFilling);
+    /// ```
+    pub fn try_new(cells: Vec<T>, options: GridOptions) -> Result<Self, LayoutError> {
+        options.filling.check()?;
+        let grid = Self::new(cells, options);
+        if grid.visible_count() > 0 && grid.options.available_width() == 0 {
+            return Err(LayoutError::NoWidth);
+        }
+
+        // A single column is as wide as its widest cell, whatever the width.
+        if grid.options.mode == Mode::SingleColumn {
+            return Ok(grid);
+        }
+        let too_wide = (0..grid.visible_count())
+            .map(|slot| grid.visible_cell(slot))
+            .find(|&index| grid.widths[index] > grid.options.available_width());
+        match too_wide {
+            Some(index) => Err(LayoutError::CellTooWide {
+                index,
+                width: grid.widths[index],
+            }),
+            None => Ok(grid),
+        }
+    }
+
+    /// The number of cells that are laid out, which are the ones that are
+    /// not hidden.
+    fn visible_count(&self) -> usize {
+        self.visible.as_ref().map_or(self.cells.len(), Vec::len)
+    }
+
+    /// The index of the cell in the given place among the visible cells.
+    fn visible_cell(&self, slot: usize) -> usize {
+        self.visible.as_ref().map_or(slot, |visible| visible[slot])
+    }
+
+    /// The place of the cell at the given index among the visible cells, or
+    /// `None` if it is hidden.
+    fn slot(&self, index: usize) -> Option<usize> {
+        match &self.visible {
+            Some(visible) => visible.binary_search(&index).ok(),
+            None => Some(index),
+        }
+    }
+
+    /// The text to display for the cell at the given index.
+    fn contents(&self, index: usize) -> &str {
+        cell_contents(&self.cells, &self.owned_contents, index)
+    }
+
+    /// The text of the cell at the given index, which only has to be
+    /// formatted if the cell did not need its contents before.
+    fn text(&self, index: usize) -> Cow<'_, str> {
+        match self.owned_contents.get(index) {
+            Some(Some(OwnedContents::Converted(text) | OwnedContents::Rewritten(text))) => {
+                Cow::Borrowed(text)
+            }
+            _ => self.cells[index].contents(),
+        }
+    }
+
+    /// Cuts (a line of) a cell so that it fits in the width of its column,
+    /// which can be narrower than the cell if it has a maximum width, and
+    /// returns the result along with its width.
+    fn fit_to_column(&self, contents: &str, column_width: usize) -> (String, usize) {
+        let ellipsis = match &self.options.overflow {
+            Overflow::Truncate(ellipsis) => ellipsis.as_str(),
+            _ => "",
+        };
+        let measure = |t: &str| self.options.measure(t);
+        text::truncate(contents, column_width, ellipsis, measure)
+    }
+
+    /// Writes the cell at the given index, using its exact bytes if it has
+    /// them and they were not changed by the options.
+    fn write_cell<O: Output>(&self, f: &mut O, index: usize) -> Result<(), O::Error> {
+        match self.owned_contents.get(index) {
+            Some(Some(OwnedContents::Rewritten(text))) => f.write_str(text),
+            Some(Some(OwnedContents::Converted(text))) => {
+                f.write_cell(text, self.cells[index].bytes())
+            }
+            _ => f.write_grid_cell(&self.cells[index]),
+        }
+    }
+
+    /// Returns the lines of the grid one at a time, as they would be
+    /// displayed but without the line terminators. Each row is only drawn
+    /// when its lines are reached, so the grid does not have to be drawn all
+    /// at once, or at all if the iterator is dropped early.
+    ///
+    /// # Panics
+    ///
+    /// The iterator panics if a cell returns an error when it is written, see
+    /// [`GridCell::write`], as [`ToString::to_string`] does. Use
+    /// [`Grid::render_into`] to get the error instead.
+    ///
+    /// ```
+    /// use term_grid::{Grid, GridOptions};
+    ///
+    /// let cells = vec!["one", "two", "three", "four"];
+    /// let grid = Grid::new(cells, GridOptions { width: 12, ..Default::default() });
+    /// let lines: Vec<String> = grid.lines().collect();
+    /// assert_eq!(lines, ["one    two", "three  four"]);
+    /// ```
+    pub fn lines(&self) -> Lines<'_, T> {
+        Lines {
+            grid: self,
+            format: self.row_format(),
+            next_part: 0,
+            pending: Vec::new().into_iter(),
+        }
+    }
+
+    /// Writes the grid to the given writer.
+    ///
+    /// Unlike the [`fmt::Display`] implementation, this writes cells that are
+    /// not valid UTF-8 byte-for-byte, see [`GridCell::bytes`].
+    ///
+    /// The grid is written as it is drawn instead of being put together in a
+    /// string first, which saves memory for large grids. It is written in
+    /// small pieces though, so the writer should be buffered.
+    ///
+    /// ```
+    /// use std::io::{self, BufWriter, Write};
+    /// use term_grid::{Grid, GridOptions};
+    ///
+    /// let grid = Grid::new(vec!["one", "two"], GridOptions::default());
+    /// let mut out = BufWriter::new(io::stdout().lock());
+    /// grid.write_to(&mut out)?;
+    /// out.flush()?;
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn write_to<W: io::Write + ?Sized>(&self, w: &mut W) -> io::Result<()> {
+        self.render_lines(&mut IoOutput(w), false)
+    }
+
+    /// Writes the grid to the end of the given string or other
+    /// [`fmt::Write`], as it is displayed.
+    ///
+    /// ```
+    /// use term_grid::{Grid, GridOptions};
+    ///
+    /// let grid = Grid::new(vec!["one", "two"], GridOptions::default());
+    /// let mut text = String::from("Files:\n");
+    /// grid.render_into(&mut text).unwrap();
+    /// assert_eq!(text, "Files:\none  two\n");
+    /// ```
+    pub fn render_into<W: fmt::Write>(&self, w: &mut W) -> fmt::Result {
+        self.render_lines(&mut FmtOutput(w), false)
+    }
+
+    /// Whether the columns are balanced, see [`Strategy::Balanced`].
+    fn balanced(&self) -> bool {
+        self.options.strategy == Strategy::Balanced
+            && self.options.direction == Direction::TopToBottom
+    }
+
+    /// The number of cells in each of the first columns of a balanced grid,
+    /// and the number of columns that have that many; the other columns have
+    /// one cell fewer.
+    fn balanced_heights(&self, num_columns: usize) -> (usize, usize) {
+        let num_cells = self.visible_count();
+        (div_ceil(num_cells, num_columns), num_cells % num_columns)
+    }
+
+    /// The row and column of the cell in the given place among the visible
+    /// cells, when they are laid out in the given number of lines and
+    /// columns.
+    fn place(&self, slot: usize, num_lines: usize, num_columns: usize) -> (usize, usize) {
+        let num_lines = num_lines.max(1);
+        let num_columns = num_columns.max(1);
+        match self.options.direction {
+            Direction::LeftToRight => (slot / num_columns, slot % num_columns),
+            Direction::RightToLeft => (slot / num_columns, num_columns - 1 - slot % num_columns),
+            Direction::TopToBottom if self.balanced() => {
+                let (height, num_full) = self.balanced_heights(num_columns);
+                if num_full == 0 || slot < num_full * height {
+                    (slot % height, slot / height)
+                } else {
+                    let rest = slot - num_full * height;
+                    (rest % (height - 1), num_full + rest / (height - 1))
+                }
+            }
+            Direction::TopToBottom => (slot % num_lines, slot / num_lines),
+            Direction::BottomToTop => (num_lines - 1 - slot % num_lines, slot / num_lines),
+        }
+    }
+
+    /// The index of the cell displayed in the given column and row, if any.
+    fn cell_index(&self, x: usize, y: usize) -> Option<usize> {
+        let num_columns = self.dimensions().widths.len();
+        if let Some(spans) = &self.dimensions().spans {
+            let slot = spans.starts.get(y * num_columns + x).copied().flatten()?;
+            return Some(self.visible_cell(slot));
+        }
+        let slot = match self.options.direction {
+            Direction::LeftToRight => y * num_columns + x,
+            Direction::RightToLeft => y * num_columns + (num_columns - 1 - x),
+            Direction::TopToBottom if self.balanced() => {
+                let (height, num_full) = self.balanced_heights(num_columns);
+                if num_full == 0 || x < num_full {
+                    x * height + y
+                } else if y < height - 1 {
+                    num_full * height + (x - num_full) * (height - 1) + y
+                } else {
+                    return None;
+                }
+            }
+            Direction::TopToBottom => y + self.dimensions().num_lines * x,
+            Direction::BottomToTop => {
+                (self.dimensions().num_lines - 1 - y) + self.dimensions().num_lines * x
+            }
+        };
+        (slot < self.visible_count()).then(|| self.visible_cell(slot))
+    }
+
+    /// The index of the cell displayed in the given column and row, unless
+    /// there is none or it only holds the place of a missing field of a
+    /// [`Table`].
+    fn shown_cell(&self, x: usize, y: usize) -> Option<usize> {
+        self.cell_index(x, y)
+            .filter(|&index| !self.placeholders.get(index).copied().unwrap_or(false))
+    }
+
+    /// Returns the row and column in which the cell at the given index is
+    /// displayed, or `None` if it is hidden or there is no such cell.
+    ///
+    /// ```
+    /// use term_grid::{Direction, Grid, GridOptions};
+    ///
+    /// let options = GridOptions {
+    ///     direction: Direction::TopToBottom,
+    ///     width: 12,
+    ///     ..Default::default()
+    /// };
+    /// let grid = Grid::new(vec!["one", "two", "three"], options);
+    /// assert_eq!(grid.to_string(), "one  three\ntwo  \n");
+    /// assert_eq!(grid.cell_position(1), Some((1, 0)));
+    /// assert_eq!(grid.cell_position(2), Some((0, 1)));
+    /// assert_eq!(grid.cell_position(3), None);
+    /// ```
+    pub fn cell_position(&self, index: usize) -> Option<(usize, usize)> {
+        if index >= self.cells.len() {
+            return None;
+        }
+        let slot = self.slot(index)?;
+        if let Some(spans) = &self.dimensions().spans {
+            let (row, column, _) = spans.places[slot];
+            return Some((row, column));
+        }
+        Some(self.place(
+            slot,
+            self.dimensions().num_lines,
+            self.dimensions().widths.len(),
+        ))
+    }
+
+    /// Returns the index of the cell displayed in the given row and column,
+    /// along with the cell itself, or `None` if nothing is displayed there,
+    /// such as at the end of the last row or column.
+    ///
+    /// A cell that spans several columns is found from any of them.
+    ///
+    /// ```
+    /// use term_grid::{Direction, Grid, GridOptions};
+    ///
+    /// let options = GridOptions {
+    ///     direction: Direction::TopToBottom,
+    ///     width: 12,
+    ///     ..Default::default()
+    /// };
+    /// let grid = Grid::new(vec!["one", "two", "three"], options);
+    /// assert_eq!(grid.cell_at(0, 1), Some((2, &"three")));
+    /// assert_eq!(grid.cell_at(1, 1), None);
+    /// ```
+    pub fn cell_at(&self, row: usize, column: usize) -> Option<(usize, &T)> {
+        let dimensions = self.dimensions();
+        let num_columns = dimensions.widths.len();
+        if row >= dimensions.num_lines || column >= num_columns {
+            return None;
+        }
+        let index = match &dimensions.spans {
+            Some(spans) => {
+                let slot = (0..=column)
+                    .rev()
+                    .find_map(|x| spans.starts[row * num_columns + x])?;
+                let (_, x, span) = spans.places[slot];
+                if column >= x + span {
+                    return None;
+                }
+                self.visible_cell(slot)
+            }
+            None => self.cell_index(column, row)?,
+        };
+        if self.placeholders.get(index).copied().unwrap_or(false) {
+            return None;
+        }
+        Some((index, &self.cells[index]))
+    }
+
+    /// Returns the cells of each row, from left to right as they are
+    /// displayed, whatever the direction. The last rows can have fewer cells
+    /// than the others.
+    ///
+    /// ```
+    /// use term_grid::{Direction, Grid, GridOptions};
+    ///
+    /// let options = GridOptions {
+    ///     direction: Direction::TopToBottom,
+    ///     width: 12,
+    ///     ..Default::default()
+    /// };
+    /// let grid = Grid::new(vec!["one", "two", "three"], options);
+    /// let rows: Vec<Vec<_>> = grid.rows().map(|row| row.copied().collect()).collect();
+    /// assert_eq!(rows, [vec!["one", "three"], vec!["two"]]);
+    /// ```
+    pub fn rows(&self) -> impl Iterator<Item = impl Iterator<Item = &T> + '_> + '_ {
+        let num_columns = self.dimensions().widths.len();
+        (0..self.dimensions().num_lines).map(move |y| {
+            (0..num_columns).filter_map(move |x| Some(&self.cells[self.shown_cell(x, y)?]))
+        })
+    }
+
+    /// Returns every cell along with the row and column it is displayed in,
+    /// in the order the cells were given. Hidden cells are left out.
+    ///
+    /// Together with [`Cell::data`], this can be used to map a position on
+    /// the screen back to whatever a cell represents.
+    ///
+    /// ```
+    /// use term_grid::{Cell, Grid, GridOptions};
+    ///
+    /// let cells = vec![
+    ///     Cell::new("one").with_data(1),
+    ///     Cell::new("two").with_data(2),
+    ///     Cell::new("three").with_data(3),
+    /// ];
+    /// let grid = Grid::new(cells, GridOptions { width: 12, ..Default::default() });
+    ///
+    /// let positions: Vec<_> = grid
+    ///     .positions()
+    ///     .map(|(cell, row, column)| (cell.data, row, column))
+    ///     .collect();
+    /// assert_eq!(positions, [(1, 0, 0), (2, 0, 1), (3, 1, 0)]);
+    /// ```
+    pub fn positions(&self) -> impl Iterator<Item = (&T, usize, usize)> + '_ {
+        self.cells.iter().enumerate().filter_map(|(index, cell)| {
+            let (row, column) = self.cell_position(index)?;
+            Some((cell, row, column))
+        })
+    }
+
+    /// The given line of the cell at the given index, when it is displayed in
+    /// a row that takes up `height` lines.
+    fn cell_line(&self, num: usize, line: usize, height: usize) -> &str {
+        let contents = self.contents(num);
+        let num_lines = contents.lines().count();
+        let vertical_alignment = self.cells[num]
+            .vertical_alignment()
+            .unwrap_or(self.options.vertical_alignment);
+        let offset = match vertical_alignment {
+            VerticalAlignment::Top => 0,
+            VerticalAlignment::Middle => (height - num_lines) / 2,
+            VerticalAlignment::Bottom => height - num_lines,
+        };
+
+        line.checked_sub(offset)
+            .and_then(|line| contents.lines().nth(line))
+            .unwrap_or("")
+    }
+
+    /// The amount of padding to put before and after (a line of) the cell at
+    /// the given index, which has the given width, to align it in column `x`
+    /// and any other columns it spans.
+    fn padding(&self, num: usize, x: usize, width: usize) -> (usize, usize) {
+        let span = self.cell_span(num);
+        let col_width = self.dimensions().span_width(x, span);
+        let padding_size = col_width - width;
+
+        let alignment = self.cells[num]
+            .alignment()
+            .unwrap_or(self.options.alignment);
+        let integer_width = self.integer_widths.get(num).copied().flatten();
+
+        match (alignment, integer_width) {
+            (Alignment::Left, _) => (0, padding_size),
+            (Alignment::Right, _) | (Alignment::Decimal(_), None) => (padding_size, 0),
+            // The column was cut short by a maximum width, or the cell is
+            // not lined up with the others as it spans columns.
+            (Alignment::Decimal(_), Some(_))
+                if span > 1
+                    || self.dimensions().decimals[x].0 + self.dimensions().decimals[x].1
+                        > col_width =>
+            {
+                (padding_size, 0)
+            }
+            (Alignment::Center, _) => {
+                let before = padding_size / 2;
+                (before, padding_size - before)
+            }
+            (Alignment::Decimal(_), Some(integer_width)) => {
+                // Right-align the block of numbers in the column, then line
+                // this cell up within the block.
+                let (integer, fraction) = self.dimensions().decimals[x];
+                let before = col_width - (integer + fraction) + (integer - integer_width);
+                (before, padding_size - before)
+            }
+        }
+    }
+
+    /// Lays the cells out in the given number of columns, regardless of the
+    /// width in the options, which can then be found with [`Grid::width`].
+    ///
+    /// With [`Direction::TopToBottom`], the cells can end up in fewer columns
+    /// than that, as every column but the last is filled up first.
+    ///
+    /// ```
+    /// use term_grid::{Grid, GridOptions};
+    ///
+    /// let mut grid = Grid::new(vec!["a", "b", "c", "d"], GridOptions::default());
+    /// grid.fit_into_columns(2);
+    /// assert_eq!(grid.to_string(), "a  b\nc  d\n");
+    /// assert_eq!(grid.width(), 4);
+    /// ```
+    pub fn fit_into_columns(&mut self, num_columns: usize) {
+        let num_cells = self.visible_count();
+        let num_columns = num_columns.clamp(1, num_cells.max(1));
+        let num_lines = div_ceil(num_cells, num_columns);
+        // Going down, the columns are filled up first, which can leave some
+        // of them empty.
+        let num_columns = match self.options.direction {
+            Direction::TopToBottom | Direction::BottomToTop if num_lines > 0 => {
+                div_ceil(num_cells, num_lines)
+            }
+            _ => num_columns,
+        };
+        self.dimensions = OnceLock::from(self.column_widths(num_lines, num_columns));
+    }
+
+    /// Lays the cells out in at most the given number of lines, using the
+    /// narrowest columns that fit, regardless of the width in the options.
+    /// The resulting width can then be found with [`Grid::width`].
+    ///
+    /// ```
+    /// use term_grid::{Grid, GridOptions};
+    ///
+    /// let mut grid = Grid::new(vec!["a", "b", "c", "d"], GridOptions::default());
+    /// grid.fit_into_lines(2);
+    /// assert_eq!(grid.to_string(), "a  b\nc  d\n");
+    /// assert_eq!(grid.width(), 4);
+    /// ```
+    pub fn fit_into_lines(&mut self, max_lines: usize) {
+        let num_cells = self.visible_count();
+        let max_lines = max_lines.clamp(1, num_cells.max(1));
+
+        // More lines usually make for a narrower grid, but not always, as the
+        // widest cells can end up in the same column.
+        let mut narrowest: Option<Dimensions> = None;
+        for num_lines in (1..=max_lines).rev() {
+            let dimensions = self.column_widths(num_lines, div_ceil(num_cells, num_lines).max(1));
+            if narrowest
+                .as_ref()
+                .map_or(true, |d| dimensions.total_width() < d.total_width())
+            {
+                narrowest = Some(dimensions);
+            }
+        }
+        if let Some(dimensions) = narrowest {
+            self.dimensions = OnceLock::from(dimensions);
+        }
+    }
+
+    /// The number of terminal columns this display takes up, based on the separator
+    /// width and the number and width of the columns.
+    pub fn width(&self) -> usize {
+        self.dimensions().total_width()
+    }
+
+    /// The number of terminal columns the lines of this display take up,
+    /// which is its [width](Grid::width) along with the indentation and the
+    /// start and end of the rows.
+    ///
+    /// ```
+    /// use term_grid::{Grid, GridOptions};
+    ///
+    /// let options = GridOptions::new().width(20).indent("> ");
+    /// let grid = Grid::new(vec!["one", "two"], options);
+    /// assert_eq!(grid.width(), 8);
+    /// assert_eq!(grid.used_width(), 10);
+    /// assert_eq!(grid.unused_width(), 10);
+    /// ```
+    pub fn used_width(&self) -> usize {
+        let options = &self.options;
+        self.width()
+            + options.measure(&options.indent)
+            + options.measure(&options.row_start)
+            + options.measure(&options.row_end)
+    }
+
+    /// The number of terminal columns of the width in the options that are
+    /// left over after the [used width](Grid::used_width), which is zero if
+    /// the grid is wider than it should be.
+    pub fn unused_width(&self) -> usize {
+        self.options.width.saturating_sub(self.used_width())
+    }
+
+    /// The number of rows this display takes up.
+    ///
+    /// Rows with multi-line cells take up more than one terminal line, see
+    /// [`Newlines::Split`].
+    pub fn row_count(&self) -> usize {
+        self.dimensions().num_lines
+    }
+
+    /// Returns the widths of the columns and of the separators in between
+    /// them.
+    ///
+    /// ```
+    /// use term_grid::{Filling, Grid, GridOptions, Layout};
+    ///
+    /// let options = GridOptions {
+    ///     filling: Filling::PerColumn(vec![Filling::Text(" | ".into()), Filling::Spaces(1)]),
+    ///     ..Default::default()
+    /// };
+    /// let grid = Grid::new(vec!["one", "two", "three"], options);
+    /// assert_eq!(grid.layout(), Layout {
+    ///     column_widths: vec![3, 3, 5],
+    ///     row_count: 1,
+    ///     gap_widths: vec![3, 1],
+    /// });
+    /// ```
+    pub fn layout(&self) -> Layout {
+        let dimensions = self.dimensions();
+        let num_gaps = dimensions.widths.len().saturating_sub(1);
+        Layout {
+            column_widths: dimensions.widths.clone(),
+            row_count: dimensions.num_lines,
+            gap_widths: (0..num_gaps).map(|x| dimensions.gap_width(x)).collect(),
+        }
+    }
+
+    /// Returns how well the cells are packed into the columns of the grid.
+    ///
+    /// ```
+    /// use term_grid::{Grid, GridOptions};
+    ///
+    /// let grid = Grid::new(vec!["one", "two", "three"], GridOptions::new().width(11));
+    /// assert_eq!(grid.to_string(), "one    two\nthree  \n");
+    ///
+    /// let report = grid.layout_report();
+    /// assert_eq!(report.empty_slots, 1);
+    /// assert_eq!(report.padding, 2);
+    /// assert_eq!(report.column_fill, [0.8, 0.5]);
+    /// assert!(!report.single_column_fallback);
+    /// ```
+    pub fn layout_report(&self) -> LayoutReport {
+        let dimensions = self.dimensions();
+        let num_columns = dimensions.widths.len();
+        let mut empty_slots = 0;
+        let mut padding = 0;
+        let mut filled = vec![0; num_columns];
+
+        for y in 0..dimensions.num_lines {
+            let mut x = 0;
+            while x < num_columns {
+                let Some(index) = self.shown_cell(x, y) else {
+                    empty_slots += 1;
+                    x += 1;
+                    continue;
+                };
+                let span = self.cell_span(index);
+                let width = self.widths[index].min(dimensions.span_width(x, span));
+                padding += dimensions.span_width(x, span) - width;
+                // A cell that spans several columns fills them from the left.
+                let mut left = width;
+                for (column, &column_width) in dimensions.widths[x..x + span].iter().enumerate() {
+                    let fill = left.min(column_width);
+                    filled[x + column] += fill;
+                    left -= fill;
+                }
+                x += span;
+            }
+        }
+
+        let column_fill = (0..num_columns)
+            .map(|x| {
+                let area = dimensions.widths[x] * dimensions.num_lines;
+                if area == 0 {
+                    0.0
+                } else {
+                    filled[x] as f64 / area as f64
+                }
+            })
+            .collect();
+        LayoutReport {
+            empty_slots,
+            padding,
+            column_fill,
+            single_column_fallback: dimensions.fallback,
+        }
+    }
+
+    /// Returns whether this display takes up as many columns as were allotted
+    /// to it.
+    ///
+    /// It’s possible to construct tables that don’t actually use up all the
+    /// columns that they could, such as when there are more columns than
+    /// cells! In this case, a column would have a width of zero. This just
+    /// checks for that.
+    pub fn is_complete(&self) -> bool {
+        self.dimensions().widths.iter().all(|&x| x > 0)
+    }
+
+    fn column_widths(&self, num_lines: usize, num_columns: usize) -> Dimensions {
+        // Balanced columns can need fewer lines than given.
+        let num_lines = if self.balanced() && num_columns > 0 {
+            self.balanced_heights(num_columns).0
+        } else {
+            num_lines
+        };
+
+        let spans = self.span_layout(num_columns);
+        let num_lines = match &spans {
+            Some(spans) => spans.starts.len() / num_columns,
+            None => num_lines,
+        };
+
+        let mut column_widths = vec![0; num_columns];
+        let mut decimals = Vec::new();
+        if !self.integer_widths.is_empty() {
+            decimals = vec![(0, 0); num_columns];
+        }
+
+        for slot in 0..self.visible_count() {
+            let index = self.visible_cell(slot);
+            let cell_width = self.widths[index];
+            let integer_width = self.integer_widths.get(index).copied().flatten();
+            let min_width = self.min_widths.get(index).copied().unwrap_or(0);
+            let column = match &spans {
+                // Cells that span columns are fitted in afterwards.
+                Some(spans) if spans.places[slot].2 > 1 => continue,
+                Some(spans) => spans.places[slot].1,
+                None => self.place(slot, num_lines, num_columns).1,
+            };
+            if let Some(integer_width) = integer_width {
+                let (integer, fraction) = &mut decimals[column];
+                *integer = (*integer).max(integer_width);
+                *fraction = (*fraction).max(cell_width.saturating_sub(integer_width));
+                column_widths[column] = column_widths[column].max(min_width);
+            } else {
+                column_widths[column] = column_widths[column].max(cell_width).max(min_width);
+            }
+        }
+
+        for (width, (integer, fraction)) in column_widths.iter_mut().zip(&decimals) {
+            *width = (*width).max(integer + fraction);
+        }
+
+        for (width, limits) in column_widths.iter_mut().zip(&self.options.columns) {
+            if let Some(min) = limits.min {
+                *width = (*width).max(min);
+            }
+            if let Some(max) = limits.max {
+                *width = (*width).min(max);
+            }
+        }
+
+        let mut dimensions = Dimensions {
+            num_lines,
+            widths: column_widths,
+            decimals,
+            separator_width: self.separator_width,
+            gap_widths: self.gap_widths(num_columns),
+            spans: None,
+            fallback: false,
+        };
+
+        // Widen the last column a cell spans if it does not fit in them.
+        if let Some(spans) = &spans {
+            for (slot, &(_, x, span)) in spans.places.iter().enumerate() {
+                let index = self.visible_cell(slot);
+                let cell_width =
+                    self.widths[index].max(self.min_widths.get(index).copied().unwrap_or(0));
+                let span_width = dimensions.span_width(x, span);
+                if span > 1 && cell_width > span_width {
+                    dimensions.widths[x + span - 1] += cell_width - span_width;
+                }
+            }
+        }
+        dimensions.spans = spans;
+
+        self.align_to_tab_stops(&mut dimensions);
+        dimensions
+    }
+
+    /// Places the cells in the given number of columns when some of them
+    /// span several columns, or returns `None` if none do.
+    fn span_layout(&self, num_columns: usize) -> Option<SpanLayout> {
+        if self.spans.is_empty() || num_columns == 0 {
+            return None;
+        }
+
+        let mut places = Vec::with_capacity(self.visible_count());
+        let mut position = 0;
+        for slot in 0..self.visible_count() {
+            let span = self.spans[self.visible_cell(slot)].min(num_columns);
+            if position % num_columns + span > num_columns {
+                position = div_ceil(position, num_columns) * num_columns;
+            }
+            let column = position % num_columns;
+            let column = match self.options.direction {
+                Direction::RightToLeft => num_columns - column - span,
+                _ => column,
+            };
+            places.push((position / num_columns, column, span));
+            position += span;
+        }
+
+        let mut starts = vec![None; div_ceil(position, num_columns) * num_columns];
+        for (slot, &(row, column, _)) in places.iter().enumerate() {
+            starts[row * num_columns + column] = Some(slot);
+        }
+        Some(SpanLayout { places, starts })
+    }
+
+    /// The number of columns that the cell at the given index takes up.
+    fn cell_span(&self, index: usize) -> usize {
+        match &self.dimensions().spans {
+            Some(spans) => self.slot(index).map_or(1, |slot| spans.places[slot].2),
+            None => 1,
+        }
+    }
+
+    /// Widens every column but the last so that the next column starts at a
+    /// tab stop, see [`GridOptions::tab_stops`].
+    fn align_to_tab_stops(&self, dimensions: &mut Dimensions) {
+        if self.options.tab_stops.is_none() {
+            return;
+        }
+        let num_gaps = dimensions.widths.len().saturating_sub(1);
+        for x in 0..num_gaps {
+            let gap_width = dimensions.gap_width(x);
+            dimensions.widths[x] = self.next_tab_stop(dimensions.widths[x] + gap_width) - gap_width;
+        }
+    }
+
+    /// Rounds the given width of a column and its separator up to the next
+    /// tab stop, if columns start at tab stops.
+    fn next_tab_stop(&self, width: usize) -> usize {
+        match self.options.tab_stops {
+            Some(tab_size) if tab_size > 0 => div_ceil(width, tab_size) * tab_size,
+            _ => width,
+        }
+    }
+
+    /// The widths of the separators between the given number of columns, if
+    /// the filling differs between gaps, or an empty list otherwise.
+    fn gap_widths(&self, num_columns: usize) -> Vec<usize> {
+        let filling = &self.options.filling;
+        if filling.is_uniform() {
+            return Vec::new();
+        }
+
+        (0..num_columns.saturating_sub(1))
+            .map(|x| filling.gap(x).width(|t| self.options.measure(t)))
+            .collect()
+    }
+
+    /// The widest a column of decimal-aligned cells could possibly get, which
+    /// is when the longest integer and fractional parts end up together.
+    fn widest_decimal_width(&self) -> usize {
+        let mut integer = 0;
+        let mut fraction = 0;
+        for index in (0..self.visible_count()).map(|slot| self.visible_cell(slot)) {
+            if let Some(integer_width) = self.integer_widths[index] {
+                integer = integer.max(integer_width);
+                fraction = fraction.max(self.widths[index].saturating_sub(integer_width));
+            }
+        }
+        integer + fraction
+    }
+
+    fn theoretical_max_num_lines(&self, maximum_width: usize) -> usize {
+        // TODO: Make code readable / efficient.
+        // Decimal-aligned cells can make their column wider than themselves,
+        // so assume the worst for them.
+        let widest_decimal_width = if self.integer_widths.is_empty() {
+            0
+        } else {
+            self.widest_decimal_width()
+        };
+        let mut widths: Vec<usize> = (0..self.visible_count())
+            .map(|slot| {
+                let index = self.visible_cell(slot);
+                let width = match self.integer_widths.get(index) {
+                    Some(Some(_)) => widest_decimal_width,
+                    _ => self.widths[index],
+                };
+                width.max(self.min_widths.get(index).copied().unwrap_or(0))
+            })
+            .collect();
+
+        // Sort widths in reverse order
+        widths.sort_unstable_by(|a, b| b.cmp(a));
+
+        let mut col_total_width_so_far = 0;
+        for (i, width) in widths.iter().enumerate() {
+            if width + col_total_width_so_far <= maximum_width {
+                col_total_width_so_far += self.next_tab_stop(self.separator_width + width);
+            } else if i == 0 {
+                // Not even the widest cell fits, so every cell needs a line.
+                return self.visible_count();
+            } else {
+                return div_ceil(self.visible_count(), i);
+            }
+        }
+
+        // If we make it to this point, we have exhausted all cells before
+        // reaching the maximum width; the theoretical max number of lines
+        // needed to display all cells is 1.
+        1
+    }
+
+    /// The dimensions when every column is as wide as the widest cell, in
+    /// which case the number of columns follows directly from the width, see
+    /// [`GridOptions::equal_columns`] and [`Strategy::Bsd`].
+    fn equal_width_dimensions(&self, maximum_width: usize) -> Option<Dimensions> {
+        let num_cells = self.visible_count();
+        let column_width = if self.integer_widths.is_empty() {
+            self.widest_cell_width
+        } else {
+            self.widest_cell_width.max(self.widest_decimal_width())
+        };
+
+        let column_step = self.next_tab_stop(column_width + self.separator_width);
+        let num_columns = if self.options.strategy == Strategy::Bsd {
+            if column_width > maximum_width {
+                return None;
+            }
+            // The cells still fit in a single column if fewer than two fit.
+            (maximum_width / column_step).max(1)
+        } else {
+            // Like the other layouts, leave at least one column of the width
+            // unused.
+            (maximum_width + self.separator_width - 1) / column_step
+        };
+        if num_columns == 0 {
+            return None;
+        }
+        let num_columns = num_columns
+            .min(self.options.max_columns.unwrap_or(num_cells).max(1))
+            .min(num_cells);
+
+        let num_lines = div_ceil(num_cells, num_columns);
+        let mut dimensions = self.column_widths(num_lines, div_ceil(num_cells, num_lines));
+        for width in &mut dimensions.widths {
+            *width = column_width;
+        }
+        self.align_to_tab_stops(&mut dimensions);
+        Some(dimensions)
+    }
+
+    fn width_dimensions(&self, maximum_width: usize) -> Option<Dimensions> {
+        // Decimal-aligned cells make a single column as wide as the longest
+        // integer and fractional parts together.
+        let widest_decimal_width = if self.integer_widths.is_empty() {
+            0
+        } else {
+            self.widest_decimal_width()
+        };
+        if self.widest_cell_width.max(widest_decimal_width) > maximum_width {
+            // Largest cell is wider than maximum width; it is impossible to fit.
+            return None;
+        }
+
+        let num_cells = self.visible_count();
+        if num_cells == 0 {
+            return Some(Dimensions {
+                num_lines: 0,
+                widths: Vec::new(),
+                decimals: Vec::new(),
+                separator_width: self.separator_width,
+                gap_widths: Vec::new(),
+                spans: None,
+                fallback: false,
+            });
+        }
+
+        if num_cells == 1 {
+            return Some(self.column_widths(1, 1));
+        }
+
+        if self.options.equal_columns || self.options.strategy == Strategy::Bsd {
+            return self.equal_width_dimensions(maximum_width);
+        }
+
+        if self.options.strategy == Strategy::Gnu {
+            return Some(self.gnu_dimensions(maximum_width));
+        }
+
+        let dimensions = self.fewest_lines_dimensions(maximum_width)?;
+        match self.options.strategy {
+            Strategy::AspectRatio { rows, columns } => {
+                Some(self.closest_to_ratio(dimensions, maximum_width, rows.max(1), columns.max(1)))
+            }
+            _ => Some(self.without_widow(dimensions, maximum_width)),
+        }
+    }
+
+    /// Uses one more line than the given dimensions if their last row has
+    /// only one or two cells and that fills the last row better, see
+    /// [`GridOptions::avoid_widows`].
+    fn without_widow(&self, dimensions: Dimensions, maximum_width: usize) -> Dimensions {
+        let across = matches!(
+            self.options.direction,
+            Direction::LeftToRight | Direction::RightToLeft
+        );
+        if !self.options.avoid_widows || !across || dimensions.num_lines < 2 {
+            return dimensions;
+        }
+
+        let num_cells = self.visible_count();
+        let is_widow = |dimensions: &Dimensions| {
+            let num_columns = dimensions.widths.len();
+            let last_row = num_cells.saturating_sub((dimensions.num_lines - 1) * num_columns);
+            last_row <= 2 && last_row < num_columns
+        };
+        if !is_widow(&dimensions) {
+            return dimensions;
+        }
+
+        let more_lines = self.fitting_dimensions(
+            dimensions.num_lines + 1,
+            maximum_width,
+            self.minimum_separator_width(),
+        );
+        match more_lines {
+            Some(more_lines) if !is_widow(&more_lines) => more_lines,
+            _ => dimensions,
+        }
+    }
+
+    /// Searches for the fewest lines that the cells fit in, in the way the
+    /// strategy asks for.
+    fn fewest_lines_dimensions(&self, maximum_width: usize) -> Option<Dimensions> {
+        let num_cells = self.visible_count();
+
+        // Capping the number of columns means there are at least this many
+        // lines.
+        let min_num_lines = match self.options.max_columns {
+            Some(max_columns) => div_ceil(num_cells, max_columns.max(1)),
+            None => 1,
+        };
+        let theoretical_max_num_lines = self
+            .theoretical_max_num_lines(maximum_width)
+            .max(min_num_lines);
+        if theoretical_max_num_lines == 1 {
+            // This if—statement is necessary for the function to work correctly
+            // for small inputs.
+            return Some(self.column_widths(1, num_cells));
+        }
+        let minimum_separator_width = self.minimum_separator_width();
+
+        if self.options.strategy == Strategy::DensestColumns {
+            // Every number of lines has to be tried, starting with the fewest.
+            return (min_num_lines..=theoretical_max_num_lines).find_map(|num_lines| {
+                self.fitting_dimensions(num_lines, maximum_width, minimum_separator_width)
+            });
+        }
+
+        // Instead of numbers of columns, try to find the fewest number of *lines*
+        // that the output will fit in.
+        let mut smallest_dimensions_yet = None;
+        for num_lines in (min_num_lines..=theoretical_max_num_lines).rev() {
+            match self.fitting_dimensions(num_lines, maximum_width, minimum_separator_width) {
+                Some(dimensions) => smallest_dimensions_yet = Some(dimensions),
+                None => return smallest_dimensions_yet,
+            }
+        }
+
+        smallest_dimensions_yet
+    }
+
+    /// The narrowest the separators can get, as the spacing can be reduced
+    /// if that means fewer lines are needed.
+    fn minimum_separator_width(&self) -> usize {
+        match (&self.options.filling, self.options.minimum_spacing) {
+            (Filling::Spaces(_), Some(minimum)) => minimum.min(self.separator_width),
+            _ => self.separator_width,
+        }
+    }
+
+    /// Uses more lines than the given dimensions if that gets the ratio of
+    /// lines to columns closer to the given one, see
+    /// [`Strategy::AspectRatio`].
+    fn closest_to_ratio(
+        &self,
+        fewest: Dimensions,
+        maximum_width: usize,
+        rows: usize,
+        columns: usize,
+    ) -> Dimensions {
+        let num_cells = self.visible_count();
+        let target = (rows as f64 / columns as f64).ln();
+        let distance = |dimensions: &Dimensions| {
+            let ratio = dimensions.num_lines as f64 / dimensions.widths.len().max(1) as f64;
+            (ratio.ln() - target).abs()
+        };
+
+        // With L lines there are about n / L columns, so the ratio is about
+        // L² / n.
+        let ideal_num_lines = (num_cells as f64 * rows as f64 / columns as f64).sqrt();
+        let minimum_separator_width = self.minimum_separator_width();
+        let mut best = fewest;
+        for candidate in [ideal_num_lines.floor(), ideal_num_lines.ceil()] {
+            let candidate = (candidate as usize).clamp(best.num_lines, num_cells);
+            let fitting = (candidate..=num_cells).find_map(|num_lines| {
+                self.fitting_dimensions(num_lines, maximum_width, minimum_separator_width)
+            });
+            if let Some(dimensions) = fitting {
+                if distance(&dimensions) < distance(&best) {
+                    best = dimensions;
+                }
+            }
+        }
+        best
+    }
+
+    /// The dimensions that GNU `ls` would choose, see [`Strategy::Gnu`].
+    fn gnu_dimensions(&self, maximum_width: usize) -> Dimensions {
+        let num_cells = self.visible_count();
+        let min_column_width = self.separator_width + 1;
+        let max_columns = (maximum_width / min_column_width)
+            .max(1)
+            .min(num_cells)
+            .min(self.options.max_columns.unwrap_or(usize::MAX).max(1));
+
+        for num_columns in (2..=max_columns).rev() {
+            let num_lines = div_ceil(num_cells, num_columns);
+            // Going down the columns leaves the last ones empty if there are
+            // not enough cells for them, but they still take up space.
+            let used_columns = match self.options.direction {
+                Direction::TopToBottom | Direction::BottomToTop => div_ceil(num_cells, num_lines),
+                Direction::LeftToRight | Direction::RightToLeft => num_columns,
+            };
+            let dimensions = self.column_widths(num_lines, used_columns);
+            let line_width: usize = (0..num_columns)
+                .map(|x| {
+                    let width = dimensions.widths.get(x).copied().unwrap_or(0);
+                    if x == num_columns - 1 {
+                        width.max(min_column_width)
+                    } else {
+                        (width + self.separator_width).max(min_column_width)
+                    }
+                })
+                .sum();
+            if line_width < maximum_width {
+                return dimensions;
+            }
+        }
+
+        self.column_widths(num_cells, 1)
+    }
+
+    /// Lays the cells out in the given number of lines, if they fit in the
+    /// width with at least the given spacing between them.
+    fn fitting_dimensions(
+        &self,
+        num_lines: usize,
+        maximum_width: usize,
+        minimum_separator_width: usize,
+    ) -> Option<Dimensions> {
+        // The number of columns is the number of cells divided by the number
+        // of lines, *rounded up*, where cells that span columns count as that
+        // many cells.
+        let num_cells = if self.spans.is_empty() {
+            self.visible_count()
+        } else {
+            (0..self.visible_count())
+                .map(|slot| self.spans[self.visible_cell(slot)])
+                .sum()
+        };
+        let num_columns = div_ceil(num_cells, num_lines);
+
+        // Early abort: if there are so many columns that the width of the
+        // *column separators* is bigger than the width of the screen, then
+        // don’t even try to tabulate it.
+        // This is actually a necessary check, because the width is stored as
+        // a usize, and making it go negative makes it huge instead, but it
+        // also serves as a speed-up.
+        let total_separator_width = if self.options.filling.is_uniform() {
+            (num_columns - 1) * minimum_separator_width
+        } else {
+            self.gap_widths(num_columns).iter().sum()
+        };
+        if maximum_width < total_separator_width {
+            return None;
+        }
+
+        // Remove the separator width from the available space.
+        let adjusted_width = maximum_width - total_separator_width;
 
-    /// An arbitrary string
-    ///
-    /// `"|"` is a common choice.
-    Text(String),
-}
+        let mut potential_dimensions = self.column_widths(num_lines, num_columns);
+        let total_cell_width = potential_dimensions.widths.iter().sum::<usize>();
+        if total_cell_width >= adjusted_width {
+            return None;
+        }
 
-impl Filling {
-    fn width(&self) -> usize {
-        match self {
-            Filling::Spaces(w) => *w,
-            Filling::Text(t) => display_width(t),
+        // Use as much of the spacing as fits.
+        if num_columns > 1 {
+            let fitting = (maximum_width - total_cell_width - 1) / (num_columns - 1);
+            potential_dimensions.separator_width = fitting.min(self.separator_width);
         }
+        Some(potential_dimensions)
     }
 }
 
-/// The options for a grid view that should be passed to [`Grid::new`]
-#[derive(Debug)]
-pub struct GridOptions {
-    /// The direction that the cells should be written in
-    pub direction: Direction,
-
-    /// The string to put in between each column of cells
-    pub filling: Filling,
-
-    /// The width to fill with the grid
-    pub width: usize,
+/// Keeps the values of a list that belong to the cells for which `keep` is
+/// `true`.
+fn retain_kept<V>(values: &mut Vec<V>, keep: &[bool]) {
+    let mut keep = keep.iter();
+    values.retain(|_| keep.next().copied().unwrap_or(true));
 }
 
-#[derive(PartialEq, Eq, Debug)]
-struct Dimensions {
-    /// The number of lines in the grid.
-    num_lines: usize,
+/// Puts the values of a list in the given order of the indices of their
+/// cells, unless the list is left empty.
+fn permute<V: Default>(values: &mut Vec<V>, order: &[usize]) {
+    if !values.is_empty() {
+        *values = order
+            .iter()
+            .map(|&index| std::mem::take(&mut values[index]))
+            .collect();
+    }
+}
 
-    /// The width of each column in the grid. The length of this vector serves
-    /// as the number of columns.
-    widths: Vec<usize>,
+/// What the grid needs to know about a cell to lay it out.
+struct Measurement {
+    owned: Option<OwnedContents>,
+    width: usize,
+    min_width: usize,
+    span: usize,
+    integer_width: Option<usize>,
+    hidden: bool,
+    multiline: bool,
 }
 
-impl Dimensions {
-    fn total_width(&self, separator_width: usize) -> usize {
-        if self.widths.is_empty() {
-            0
-        } else {
-            let values = self.widths.iter().sum::<usize>();
-            let separators = separator_width * (self.widths.len() - 1);
-            values + separators
+/// Puts the value for the cell at the given index in a list that is left
+/// empty for as long as all its values are the default, where the list has
+/// values for `num_stored` cells so far, either in between them or instead
+/// of the value that was there.
+fn put<V: Clone + PartialEq>(
+    values: &mut Vec<V>,
+    index: usize,
+    value: V,
+    default: V,
+    (num_stored, insert): (usize, bool),
+) {
+    if values.is_empty() {
+        if value == default {
+            return;
         }
+        values.resize(num_stored, default);
+    }
+    if insert {
+        values.insert(index, value);
+    } else {
+        values[index] = value;
     }
 }
 
-/// Everything needed to format the cells with the grid options.
-#[derive(Debug)]
-pub struct Grid<T: AsRef<str>> {
-    options: GridOptions,
-    cells: Vec<T>,
-    widths: Vec<usize>,
-    widest_cell_width: usize,
-    dimensions: Dimensions,
+/// The text to display for the cell at the given index, which is either
+/// borrowed from the cell or owned by the grid.
+fn cell_contents<'a, T: GridCell>(
+    cells: &'a [T],
+    owned_contents: &'a [Option<OwnedContents>],
+    index: usize,
+) -> &'a str {
+    match owned_contents.get(index) {
+        Some(Some(OwnedContents::Converted(text) | OwnedContents::Rewritten(text))) => text,
+        _ => match cells[index].contents() {
+            Cow::Borrowed(text) => text,
+            Cow::Owned(_) => unreachable!("owned contents are stored when the grid is created"),
+        },
+    }
 }
 
-impl<T: AsRef<str>> Grid<T> {
-    /// Creates a new grid view with the given cells and options
-    pub fn new(cells: Vec<T>, options: GridOptions) -> Self {
-        let widths: Vec<usize> = cells.iter().map(|c| display_width(c.as_ref())).collect();
-        let widest_cell_width = widths.iter().copied().max().unwrap_or(0);
-        let width = options.width;
+impl<'a, T: GridCell> Grid<CellRef<'a, T>> {
+    /// Creates a new grid view of the given cells, without taking ownership
+    /// of them.
+    ///
+    /// ```
+    /// use term_grid::{Cell, Grid, GridOptions};
+    ///
+    /// let cells = vec![Cell::new("one"), Cell::new("two")];
+    /// let grid = Grid::from_slice(&cells, GridOptions::default());
+    /// assert_eq!(grid.to_string(), "one  two\n");
+    /// ```
+    pub fn from_slice(cells: &'a [T], options: GridOptions) -> Self {
+        Self::new(cells.iter().map(CellRef).collect(), options)
+    }
+}
 
-        let mut grid = Self {
-            options,
-            cells,
-            widths,
-            widest_cell_width,
-            dimensions: Dimensions {
-                num_lines: 0,
-                widths: Vec::new(),
+/// Displays the grid as text, or with box-drawing borders around and in
+/// between the columns with the alternate flag (`{:#}`).
+///
+/// A width given to the formatter (`{:60}`) is used instead of the width in
+/// the options, for which the cells are laid out again without being
+/// measured again.
+///
+/// ```
+/// use term_grid::{Grid, GridOptions};
+///
+/// let grid = Grid::new(vec!["a", "bb", "c"], GridOptions::default());
+/// assert_eq!(format!("{grid:#}"), "\
+/// ┌───┬────┬───┐
+/// │ a │ bb │ c │
+/// └───┴────┴───┘
+/// ");
+/// assert_eq!(format!("{grid:4}"), "a\nbb\nc\n");
+/// ```
+impl<T: GridCell> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        let boxed = f.alternate();
+        match f.width() {
+            Some(width) if width != self.options.width => {
+                self.with_width(width).render_lines(f, boxed)
+            }
+            _ => self.render_lines(f, boxed),
+        }
+    }
+}
+
+impl<T: GridCell> Grid<T> {
+    /// A view of the grid that is laid out for another width, reusing the
+    /// measurements of the cells where possible.
+    fn with_width(&self, width: usize) -> Grid<CellRef<'_, T>> {
+        let mut grid = Grid {
+            options: GridOptions {
+                width,
+                ..self.options.clone()
             },
+            cells: self.cells.iter().map(CellRef).collect(),
+            visible: self.visible.clone(),
+            owned_contents: self.owned_contents.clone(),
+            multiline: self.multiline,
+            widths: self.widths.clone(),
+            min_widths: self.min_widths.clone(),
+            spans: self.spans.clone(),
+            placeholders: self.placeholders.clone(),
+            integer_widths: self.integer_widths.clone(),
+            widest_cell_width: self.widest_cell_width,
+            separator_width: self.separator_width,
+            dimensions: OnceLock::new(),
         };
+        if grid.options.overflow != Overflow::Extend {
+            grid.measure_cells();
+        }
+        grid
+    }
+}
 
-        grid.dimensions = grid.width_dimensions(width).unwrap_or(Dimensions {
-            num_lines: grid.cells.len(),
-            widths: vec![widest_cell_width],
-        });
+/// A grid can be a cell of another grid or of a [`Table`], such as a grid
+/// of tags in a column of a table. It takes up as many lines as it has, so
+/// the outer grid should use [`Newlines::Split`], and it is laid out within
+/// its own width, not that of its column.
+///
+/// ```
+/// use term_grid::{Grid, GridOptions, Newlines, Table, TableOptions};
+///
+/// let tags = |cells| Grid::new(cells, GridOptions { width: 12, ..Default::default() });
+/// let table = Table::new(
+///     vec![vec![tags(vec!["x"])], vec![tags(vec!["red", "green", "blue"])]],
+///     GridOptions { newlines: Newlines::Split, ..Default::default() },
+///     TableOptions::default(),
+/// );
+/// assert_eq!(table.to_string(), "x\nred   green\nblue  \n");
+/// ```
+impl<T: GridCell> GridCell for Grid<T> {
+    fn contents(&self) -> Cow<'_, str> {
+        let mut text = self.to_string();
+        if text.ends_with(&self.options.line_terminator) {
+            text.truncate(text.len() - self.options.line_terminator.len());
+        }
+        Cow::Owned(text)
+    }
 
-        grid
+    fn width(&self) -> Option<usize> {
+        Some(Grid::width(self))
     }
+}
 
-    /// The number of terminal columns this display takes up, based on the separator
-    /// width and the number and width of the columns.
-    pub fn width(&self) -> usize {
-        self.dimensions.total_width(self.options.filling.width())
+/// Grids are equal if their cells, options and layouts are equal.
+impl<T: GridCell + PartialEq> PartialEq for Grid<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cells == other.cells
+            && self.options == other.options
+            && self.dimensions() == other.dimensions()
     }
+}
 
-    /// The number of rows this display takes up.
-    pub fn row_count(&self) -> usize {
-        self.dimensions.num_lines
+impl<T: GridCell + Eq> Eq for Grid<T> {}
+
+/// Collects cells into a grid with the default options, which can be changed
+/// with [`Grid::with_options`].
+impl<T: GridCell> FromIterator<T> for Grid<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(cells: I) -> Self {
+        Self::new(cells.into_iter().collect(), GridOptions::default())
     }
+}
 
-    /// Returns whether this display takes up as many columns as were allotted
-    /// to it.
-    ///
-    /// It’s possible to construct tables that don’t actually use up all the
-    /// columns that they could, such as when there are more columns than
-    /// cells! In this case, a column would have a width of zero. This just
-    /// checks for that.
-    pub fn is_complete(&self) -> bool {
-        self.dimensions.widths.iter().all(|&x| x > 0)
+/// Adds cells to the end of the grid, like [`Grid::add`] does.
+impl<T: GridCell> Extend<T> for Grid<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, cells: I) {
+        let start = self.cells.len();
+        self.cells.extend(cells);
+        self.cells_added(start);
     }
+}
 
-    fn column_widths(&self, num_lines: usize, num_columns: usize) -> Dimensions {
-        let mut column_widths = vec![0; num_columns];
-        for (index, cell_width) in self.widths.iter().copied().enumerate() {
-            let index = match self.options.direction {
-                Direction::LeftToRight => index % num_columns,
-                Direction::TopToBottom => index / num_lines,
-            };
-            if cell_width > column_widths[index] {
-                column_widths[index] = cell_width;
-            }
+impl<T: GridCell> Grid<T> {
+    /// Draws the grid, with or without a box around it, and indents and
+    /// terminates every line.
+    fn render_lines<O: Output>(&self, f: &mut O, boxed: bool) -> Result<(), O::Error> {
+        let options = &self.options;
+        if options.indent.is_empty() && options.line_terminator == "\n" && options.final_newline {
+            return self.render_either(f, boxed);
         }
-
-        Dimensions {
-            num_lines,
-            widths: column_widths,
+        let mut lines = LineOutput {
+            inner: f,
+            indent: &options.indent,
+            terminator: &options.line_terminator,
+            line_start: true,
+            pending_terminator: false,
+        };
+        self.render_either(&mut lines, boxed)?;
+        if options.final_newline {
+            lines.end_line()?;
         }
+        Ok(())
     }
 
-    fn theoretical_max_num_lines(&self, maximum_width: usize) -> usize {
-        // TODO: Make code readable / efficient.
-        let mut widths = self.widths.clone();
+    fn render_either<O: Output>(&self, f: &mut O, boxed: bool) -> Result<(), O::Error> {
+        if let Mode::Stream(separator) = &self.options.mode {
+            self.render_stream(f, separator)
+        } else if boxed {
+            self.render_boxed(f)
+        } else {
+            self.render(f)
+        }
+    }
 
-        // Sort widths in reverse order
-        widths.sort_unstable_by(|a, b| b.cmp(a));
+    /// Writes the cells one after the other, starting a new line when the
+    /// next cell would reach the width, like GNU `ls -m`.
+    fn render_stream<O: Output>(&self, f: &mut O, separator: &str) -> Result<(), O::Error> {
+        let width = self.options.available_width();
+        let separator_width = self.options.measure(separator);
+        let line_break = separator.trim_end();
+        let num_cells = self.visible_count();
 
-        let mut col_total_width_so_far = 0;
-        for (i, width) in widths.iter().enumerate() {
-            if width + col_total_width_so_far <= maximum_width {
-                col_total_width_so_far += self.options.filling.width() + width;
-            } else {
-                return div_ceil(self.cells.len(), i);
+        let mut position = 0;
+        for slot in 0..num_cells {
+            let index = self.visible_cell(slot);
+            let cell_width = self.widths[index];
+            if slot > 0 {
+                if position + separator_width + cell_width < width {
+                    f.write_str(separator)?;
+                    position += separator_width;
+                } else {
+                    f.write_str(line_break)?;
+                    f.write_str("\n")?;
+                    position = 0;
+                }
             }
+            self.write_cell(f, index)?;
+            position += cell_width;
         }
-
-        // If we make it to this point, we have exhausted all cells before
-        // reaching the maximum width; the theoretical max number of lines
-        // needed to display all cells is 1.
-        1
+        if num_cells > 0 {
+            f.write_str("\n")?;
+        }
+        Ok(())
     }
 
-    fn width_dimensions(&self, maximum_width: usize) -> Option<Dimensions> {
-        if self.widest_cell_width > maximum_width {
-            // Largest cell is wider than maximum width; it is impossible to fit.
-            return None;
+    /// The width of the widest column, or of the whole grid if cells can span
+    /// several columns, which is the most padding a cell can need.
+    fn widest_span_width(&self) -> usize {
+        if self.dimensions().spans.is_some() {
+            self.dimensions().total_width() + 3 * self.dimensions().widths.len()
+        } else {
+            self.dimensions().widths.iter().copied().max().unwrap_or(0)
         }
+    }
 
-        if self.cells.is_empty() {
-            return Some(Dimensions {
-                num_lines: 0,
-                widths: Vec::new(),
-            });
+    /// The number of lines that row `y` takes up, which is more than one if
+    /// it has multi-line cells.
+    fn row_height(&self, y: usize) -> usize {
+        if self.multiline {
+            (0..self.dimensions().widths.len())
+                .filter_map(|x| self.cell_index(x, y))
+                .map(|num| self.contents(num).lines().count().max(1))
+                .max()
+                .unwrap_or(1)
+        } else {
+            1
         }
+    }
 
-        if self.cells.len() == 1 {
-            let cell_widths = self.widths[0];
-            return Some(Dimensions {
-                num_lines: 1,
-                widths: vec![cell_widths],
-            });
-        }
+    /// The text to write for (a line of) the cell at the given index in
+    /// column `x`, and the padding to put before and after it. The text is
+    /// `None` if the cell should be written with [`Grid::write_cell`].
+    fn cell_text(
+        &self,
+        num: usize,
+        x: usize,
+        line: usize,
+        height: usize,
+    ) -> (Option<Cow<'_, str>>, usize, usize) {
+        let contents = self.multiline.then(|| self.cell_line(num, line, height));
+        let width = match contents {
+            Some(contents) => self.options.measure(contents),
+            None => self.widths[num],
+        };
 
-        let theoretical_max_num_lines = self.theoretical_max_num_lines(maximum_width);
-        if theoretical_max_num_lines == 1 {
-            // This if—statement is necessary for the function to work correctly
-            // for small inputs.
-            return Some(Dimensions {
-                num_lines: 1,
-                widths: self.widths.clone(),
-            });
+        // Cells can be wider than their column if it has a maximum width.
+        let column_width = self.dimensions().span_width(x, self.cell_span(num));
+        let (text, width) = if width > column_width {
+            let text = contents.map_or_else(|| self.text(num), Cow::Borrowed);
+            let (fitted, width) = self.fit_to_column(&text, column_width);
+            (Some(Cow::Owned(fitted)), width)
+        } else {
+            (contents.map(Cow::Borrowed), width)
+        };
+
+        let (before, after) = self.padding(num, x, width);
+        (text, before, after)
+    }
+
+    /// Draws the grid with box-drawing characters around and in between the
+    /// columns. Every column is padded, and the filling is not used.
+    fn render_boxed<O: Output>(&self, f: &mut O) -> Result<(), O::Error> {
+        let widths = &self.dimensions().widths;
+        if widths.is_empty() {
+            return Ok(());
         }
-        // Instead of numbers of columns, try to find the fewest number of *lines*
-        // that the output will fit in.
-        let mut smallest_dimensions_yet = None;
-        for num_lines in (1..=theoretical_max_num_lines).rev() {
-            // The number of columns is the number of cells divided by the number
-            // of lines, *rounded up*.
-            let num_columns = div_ceil(self.cells.len(), num_lines);
-
-            // Early abort: if there are so many columns that the width of the
-            // *column separators* is bigger than the width of the screen, then
-            // don’t even try to tabulate it.
-            // This is actually a necessary check, because the width is stored as
-            // a usize, and making it go negative makes it huge instead, but it
-            // also serves as a speed-up.
-            let total_separator_width = (num_columns - 1) * self.options.filling.width();
-            if maximum_width < total_separator_width {
-                continue;
-            }
-
-            // Remove the separator width from the available space.
-            let adjusted_width = maximum_width - total_separator_width;
-
-            let potential_dimensions = self.column_widths(num_lines, num_columns);
-            if potential_dimensions.widths.iter().sum::<usize>() < adjusted_width {
-                smallest_dimensions_yet = Some(potential_dimensions);
-            } else {
-                return smallest_dimensions_yet;
+
+        let widest_column_width = self.widest_span_width();
+        let padding_char = self.options.padding;
+        let padding = padding_char.to_string().repeat(widest_column_width);
+        let padding_len = padding_char.len_utf8();
+        let blank = " ".repeat(widest_column_width + 2);
+        let rule = |f: &mut O, left: &str, middle: &str, right: &str| {
+            f.write_str(left)?;
+            for (x, width) in widths.iter().enumerate() {
+                if x > 0 {
+                    f.write_str(middle)?;
+                }
+                f.write_str(&"─".repeat(width + 2))?;
+            }
+            f.write_str(right)?;
+            f.write_str("\n")
+        };
+
+        let box_width = widths.iter().map(|width| width + 3).sum::<usize>() + 1;
+        self.render_caption(f, self.options.title.as_ref(), box_width)?;
+        rule(f, "┌", "┬", "┐")?;
+        for y in 0..self.dimensions().num_lines {
+            if y > 0 {
+                for _ in 0..self.options.row_spacing {
+                    f.write_str("│")?;
+                    for &width in widths {
+                        f.write_str(&blank[0..width + 2])?;
+                        f.write_str("│")?;
+                    }
+                    f.write_str("\n")?;
+                }
+            }
+            if let Some(separator) = &self.options.row_separator {
+                if y > 0 && y % separator.every.max(1) == 0 {
+                    rule(f, "├", "┼", "┤")?;
+                }
+            }
+
+            let height = self.row_height(y);
+            for line in 0..height {
+                f.write_str("│")?;
+                // The columns up to this one are taken up by a cell that
+                // spans them.
+                let mut covered = 0;
+                for (x, &width) in widths.iter().enumerate() {
+                    if x < covered {
+                        continue;
+                    }
+                    f.write_str(" ")?;
+                    if let Some(num) = self.cell_index(x, y) {
+                        let (text, before, after) = self.cell_text(num, x, line, height);
+                        let span = self.cell_span(num);
+                        covered = x + span;
+                        // The lines in between the columns are wider than
+                        // the separators.
+                        let span_width = widths[x..covered].iter().sum::<usize>() + 3 * (span - 1);
+                        let after = (after + span_width)
+                            .saturating_sub(self.dimensions().span_width(x, span));
+                        f.write_str(&padding[0..before * padding_len])?;
+                        match &text {
+                            Some(text) => f.write_str(text)?,
+                            None => self.write_cell(f, num)?,
+                        }
+                        f.write_str(&padding[0..after * padding_len])?;
+                    } else {
+                        f.write_str(&blank[0..width])?;
+                    }
+                    f.write_str(" │")?;
+                }
+                f.write_str("\n")?;
             }
         }
 
-        None
+        match &self.options.footer {
+            Some(Footer::Columns(texts)) => {
+                rule(f, "├", "┼", "┤")?;
+                f.write_str("│")?;
+                for (x, &width) in widths.iter().enumerate() {
+                    let text = texts.get(x).map_or("", String::as_str);
+                    let padding = width.saturating_sub(self.options.measure(text));
+                    let (before, after) = split_padding(self.options.alignment, padding);
+                    f.write_str(" ")?;
+                    f.write_str(&blank[0..before])?;
+                    f.write_str(text)?;
+                    f.write_str(&blank[0..after])?;
+                    f.write_str(" │")?;
+                }
+                f.write_str("\n")?;
+                rule(f, "└", "┴", "┘")?;
+            }
+            Some(Footer::Text(text)) => {
+                rule(f, "└", "┴", "┘")?;
+                f.write_str(text)?;
+                f.write_str("\n")?;
+            }
+            None => rule(f, "└", "┴", "┘")?,
+        }
+        self.render_caption(f, self.options.caption.as_ref(), box_width)
     }
-}
 
-impl<T: AsRef<str>> fmt::Display for Grid<T> {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
-        let separator = match &self.options.filling {
-            Filling::Spaces(n) => " ".repeat(*n),
-            Filling::Text(s) => s.clone(),
+    /// Writes a title or caption line aligned within the given width, if
+    /// there is one. Text that is too wide is written as it is.
+    fn render_caption<O: Output>(
+        &self,
+        f: &mut O,
+        caption: Option<&Caption>,
+        width: usize,
+    ) -> Result<(), O::Error> {
+        let Some(caption) = caption else {
+            return Ok(());
         };
+        let padding = width.saturating_sub(self.options.measure(&caption.text));
+        let (before, _) = split_padding(caption.alignment, padding);
+        f.write_str(&" ".repeat(before))?;
+        f.write_str(&caption.text)?;
+        f.write_str("\n")
+    }
+
+    /// Writes the footer below the rows of the grid, if there is one.
+    fn render_footer<O: Output>(&self, f: &mut O) -> Result<(), O::Error> {
+        match &self.options.footer {
+            Some(Footer::Text(text)) => {
+                f.write_str(text)?;
+                f.write_str("\n")
+            }
+            Some(Footer::Columns(texts)) => {
+                let widths = &self.dimensions().widths;
+                let num_texts = texts.len().min(widths.len());
+                let mut line = String::new();
+                for (x, text) in texts.iter().take(num_texts).enumerate() {
+                    let padding = widths[x].saturating_sub(self.options.measure(text));
+                    let (before, after) = split_padding(self.options.alignment, padding);
+                    let last = x == num_texts - 1;
+                    line.push_str(&" ".repeat(before));
+                    line.push_str(text);
+                    if !last {
+                        line.push_str(&" ".repeat(after + self.dimensions().gap_width(x)));
+                    }
+                }
+                // The footer lines up with the rows, so it is framed like them.
+                f.write_str(&self.options.row_start)?;
+                f.write_str(&line)?;
+                if !self.options.row_end.is_empty() {
+                    let padding = self.dimensions().total_width();
+                    let padding = padding.saturating_sub(self.options.measure(&line));
+                    f.write_str(&" ".repeat(padding))?;
+                    f.write_str(&self.options.row_end)?;
+                }
+                f.write_str("\n")
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Works out what is needed to draw the rows of the grid.
+    fn row_format(&self) -> RowFormat {
+        let num_columns = self.dimensions().widths.len();
 
-        // Initialize a buffer of spaces. The idea here is that any cell
-        // that needs padding gets a slice of this buffer of the needed
-        // size. This avoids the need of creating a string of spaces for
-        // each cell that needs padding.
+        // Initialize a buffer of padding characters. The idea here is that
+        // any cell that needs padding gets a slice of this buffer of the
+        // needed size. This avoids the need of creating a string of spaces
+        // for each cell that needs padding.
         //
         // We overestimate how many spaces we need, but this is not
         // part of the loop and it's therefore not super important to
         // get exactly right.
-        let padding = " ".repeat(self.widest_cell_width);
+        let widest_column_width = self.widest_span_width();
+        let padding_char = self.options.padding;
+        let padding = padding_char.to_string().repeat(widest_column_width);
+        let padding_len = padding_char.len_utf8();
 
-        for y in 0..self.dimensions.num_lines {
-            for x in 0..self.dimensions.widths.len() {
-                let num = match self.options.direction {
-                    Direction::LeftToRight => y * self.dimensions.widths.len() + x,
-                    Direction::TopToBottom => y + self.dimensions.num_lines * x,
-                };
+        // The separator after each column but the last, or the leader that
+        // fills the gap. A leader spans the padding after a cell, the
+        // separator and the padding before the next cell, so it can be twice
+        // as long.
+        let gaps: Vec<(String, Option<(String, usize)>)> = (0..num_columns.saturating_sub(1))
+            .map(|x| match self.options.filling.gap(x) {
+                Filling::Spaces(_) => (" ".repeat(self.dimensions().gap_width(x)), None),
+                Filling::Text(s) => (s.clone(), None),
+                Filling::Leader(c) => (
+                    String::new(),
+                    Some((
+                        c.to_string().repeat(2 * widest_column_width + 1),
+                        c.len_utf8(),
+                    )),
+                ),
+                Filling::PerColumn(_) | Filling::Cycle(_) => {
+                    unreachable!("gaps are never filled with multiple fillings")
+                }
+            })
+            .collect();
+
+        let row_separator = self.options.row_separator.as_ref().map(|separator| {
+            let width = self.dimensions().total_width();
+            let measure = |t: &str| self.options.measure(t);
+            let text_width = measure(&separator.text).max(1);
+            let line = separator.text.repeat(div_ceil(width, text_width).max(1));
+            (
+                separator.every.max(1),
+                text::truncate(&line, width, "", measure).0,
+            )
+        });
 
-                // Abandon a line mid-way through if that’s where the cells end
-                if num >= self.cells.len() {
+        // Runs of spaces are only turned into tabs if everything in between
+        // cells is spaces.
+        let tab_size = self.options.padding_tabs.filter(|&size| {
+            size > 0
+                && padding_char == ' '
+                && gaps.iter().all(|(separator, leader)| {
+                    leader.is_none() && separator.chars().all(|c| c == ' ')
+                })
+        });
+        // The rows have to be as wide as the grid for the end to line up.
+        let pad_last_column = self.options.pad_last_column || !self.options.row_end.is_empty();
+        let indent_width = self.options.measure(&self.options.indent)
+            + self.options.measure(&self.options.row_start);
+
+        RowFormat {
+            num_columns,
+            padding,
+            padding_len,
+            gaps,
+            row_separator,
+            tab_size,
+            pad_last_column,
+            indent_width,
+            total_width: self.dimensions().total_width(),
+        }
+    }
+
+    fn render<O: Output>(&self, f: &mut O) -> Result<(), O::Error> {
+        let format = self.row_format();
+        self.render_caption(f, self.options.title.as_ref(), format.total_width)?;
+        for y in 0..self.dimensions().num_lines {
+            self.render_row(f, y, &format)?;
+        }
+        self.render_footer(f)?;
+        self.render_caption(f, self.options.caption.as_ref(), format.total_width)
+    }
+
+    /// Writes row `y`, along with the spacing or separator above it.
+    fn render_row<O: Output>(
+        &self,
+        f: &mut O,
+        y: usize,
+        format: &RowFormat,
+    ) -> Result<(), O::Error> {
+        let RowFormat {
+            num_columns,
+            ref padding,
+            padding_len,
+            ref gaps,
+            ref row_separator,
+            tab_size,
+            pad_last_column,
+            indent_width,
+            total_width,
+        } = *format;
+        let row_start = self.options.row_start.as_str();
+        let row_end = self.options.row_end.as_str();
+
+        if y > 0 {
+            for _ in 0..self.options.row_spacing {
+                f.write_str(row_start)?;
+                if !row_end.is_empty() {
+                    f.write_str(&" ".repeat(total_width))?;
+                    f.write_str(row_end)?;
+                }
+                f.write_str("\n")?;
+            }
+        }
+        if let Some((every, line)) = &row_separator {
+            if y > 0 && y % every == 0 {
+                f.write_str(row_start)?;
+                f.write_str(line)?;
+                f.write_str(row_end)?;
+                f.write_str("\n")?;
+            }
+        }
+
+        let height = self.row_height(y);
+        let (style_start, style_end) = match self.options.row_style {
+            Some(row_style) => row_style(y),
+            None => (String::new(), String::new()),
+        };
+
+        for line in 0..height {
+            // The length of the leader that still has to be written
+            // before the next cell.
+            let mut pending_leader = 0;
+
+            // When writing tabs, the blank space that still has to be
+            // written, as positions on the line, and where the current
+            // column starts.
+            let mut pending_blank: Option<(usize, usize)> = None;
+            let mut column_start = indent_width;
+
+            // The columns up to this one are taken up by a cell that
+            // spans them.
+            let mut covered = 0;
+
+            f.write_str(&style_start)?;
+            f.write_str(row_start)?;
+            for x in 0..num_columns {
+                if x < covered {
                     continue;
                 }
+                let cell = self.shown_cell(x, y);
+                let span = cell.map_or(1, |num| self.cell_span(num));
+                covered = x + span;
+                let last_column = covered - 1;
+                let gap_width = if last_column < num_columns - 1 {
+                    self.dimensions().gap_width(last_column)
+                } else {
+                    0
+                };
+                let column_end = column_start + self.dimensions().span_width(x, span);
+                let next_column_start = column_end + gap_width;
 
-                let contents = &self.cells[num];
-                let width = self.widths[num];
-                let last_in_row = x == self.dimensions.widths.len() - 1;
+                let Some(num) = cell else {
+                    // Leave the column blank if there are cells after
+                    // it, as at the start of the last row of a grid
+                    // written right to left.
+                    let cells_after = (x + 1..num_columns).any(|x| self.shown_cell(x, y).is_some());
+                    if (cells_after || pad_last_column) && tab_size.is_some() {
+                        let from = pending_blank.map_or(column_start, |(from, _)| from);
+                        pending_blank = Some((from, next_column_start));
+                    } else if cells_after || pad_last_column {
+                        let mut blank = self.dimensions().widths[x] + gap_width;
+                        // Finish a leader that was started for this cell.
+                        if pending_leader > 0 {
+                            blank += pending_leader + 2;
+                            pending_leader = 0;
+                        }
+                        f.write_str(&" ".repeat(blank))?;
+                    }
+                    // Otherwise, abandon a line mid-way through if that’s
+                    // where the cells end.
+                    column_start = next_column_start;
+                    continue;
+                };
 
-                let col_width = self.dimensions.widths[x];
-                let padding_size = col_width - width;
+                let (text, before, after) = self.cell_text(num, x, line, height);
+                // GNU ls leaves out the padding after the last cell of
+                // a row that ends early too, and so do tables after the
+                // last field of a short row.
+                let last_in_row = last_column == num_columns - 1
+                    || ((self.options.strategy == Strategy::Gnu || !self.placeholders.is_empty())
+                        && !pad_last_column
+                        && !(covered..num_columns).any(|x| self.shown_cell(x, y).is_some()));
 
                 // The final column doesn’t need to have trailing spaces,
                 // as long as it’s left-aligned.
@@ -294,21 +4272,172 @@ impl<T: AsRef<str>> fmt::Display for Grid<T> {
                 // above, so we don't need to call `" ".repeat(n)` each loop.
                 // We also only call `write_str` when we actually need padding as
                 // another optimization.
-                f.write_str(contents.as_ref())?;
-                if !last_in_row {
-                    if padding_size > 0 {
-                        f.write_str(&padding[0..padding_size])?;
+                if let Some(tab_size) = tab_size {
+                    let blank_end = column_start + before;
+                    let from = pending_blank.take().map_or(blank_end, |(from, _)| from);
+                    write_blank(f, from, blank_end, tab_size)?;
+                    match &text {
+                        Some(text) => f.write_str(text)?,
+                        None => self.write_cell(f, num)?,
+                    }
+                    if !last_in_row || pad_last_column {
+                        let to = if last_in_row {
+                            column_end
+                        } else {
+                            next_column_start
+                        };
+                        pending_blank = Some((column_end - after, to));
+                    }
+                    column_start = next_column_start;
+                    continue;
+                }
+
+                let leader = x.checked_sub(1).and_then(|x| gaps[x].1.as_ref());
+                if let (Some((leader, leader_len)), true) = (leader, pending_leader > 0) {
+                    f.write_str(" ")?;
+                    f.write_str(&leader[0..(pending_leader + before) * leader_len])?;
+                    f.write_str(" ")?;
+                } else if before > 0 {
+                    f.write_str(&padding[0..before * padding_len])?;
+                }
+                match &text {
+                    Some(text) => f.write_str(text)?,
+                    None => self.write_cell(f, num)?,
+                }
+                if last_in_row {
+                    if pad_last_column && after > 0 {
+                        f.write_str(&padding[0..after * padding_len])?;
+                    }
+                    continue;
+                }
+                let (separator, leader) = &gaps[last_column];
+                if leader.is_some() {
+                    pending_leader = after + 1;
+                } else {
+                    if after > 0 {
+                        f.write_str(&padding[0..after * padding_len])?;
                     }
-                    f.write_str(&separator)?;
+                    f.write_str(separator)?;
                 }
+                column_start = next_column_start;
             }
+            if let (Some(tab_size), Some((from, to))) = (tab_size, pending_blank) {
+                write_blank(f, from, to, tab_size)?;
+            }
+            f.write_str(row_end)?;
+            f.write_str(&style_end)?;
             f.write_str("\n")?;
         }
-
         Ok(())
     }
 }
 
+/// An iterator over the lines of a grid, returned by [`Grid::lines`].
+pub struct Lines<'a, T: GridCell> {
+    grid: &'a Grid<T>,
+    format: RowFormat,
+
+    /// The next part of the grid to draw, which is the title, then each row,
+    /// then the footer and the caption.
+    next_part: usize,
+
+    /// The lines of the last part that was drawn that are still to come.
+    pending: std::vec::IntoIter<String>,
+}
+
+impl<T: GridCell> Iterator for Lines<'_, T> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        loop {
+            if let Some(line) = self.pending.next() {
+                return Some(line);
+            }
+            let grid = self.grid;
+            let num_rows = grid.dimensions().num_lines;
+            if self.next_part > num_rows + 1 {
+                return None;
+            }
+
+            let mut text = String::new();
+            let f = &mut FmtOutput(&mut text);
+            let result = match (&grid.options.mode, self.next_part) {
+                (Mode::Stream(separator), _) => {
+                    self.next_part = num_rows + 1;
+                    grid.render_stream(f, separator)
+                }
+                (_, 0) => {
+                    grid.render_caption(f, grid.options.title.as_ref(), self.format.total_width)
+                }
+                (_, part) if part <= num_rows => grid.render_row(f, part - 1, &self.format),
+                _ => grid.render_footer(f).and_then(|()| {
+                    grid.render_caption(f, grid.options.caption.as_ref(), self.format.total_width)
+                }),
+            };
+            result.expect("a cell returned an error when it was written");
+            self.next_part += 1;
+
+            let indent = &grid.options.indent;
+            self.pending = text
+                .lines()
+                .map(|line| match line {
+                    "" => String::new(),
+                    line => format!("{indent}{line}"),
+                })
+                .collect::<Vec<_>>()
+                .into_iter();
+        }
+    }
+}
+
+/// What is needed to draw the rows of a grid, which is worked out once
+/// before the first row.
+struct RowFormat {
+    num_columns: usize,
+
+    /// Padding characters to take slices of, enough for any cell.
+    padding: String,
+    padding_len: usize,
+
+    /// The separator after each column but the last, or the leader that
+    /// fills the gap with its length in bytes.
+    gaps: Vec<(String, Option<(String, usize)>)>,
+
+    /// How often to put the row separator, and the line to put.
+    row_separator: Option<(usize, String)>,
+
+    /// The tab size if runs of spaces are turned into tabs.
+    tab_size: Option<usize>,
+    pad_last_column: bool,
+
+    /// The width before the first column.
+    indent_width: usize,
+    total_width: usize,
+}
+
+/// Writes blank space from one position on a line to another, using a tab
+/// wherever it replaces more than one space, like GNU `ls` does.
+fn write_blank<O: Output>(
+    f: &mut O,
+    from: usize,
+    to: usize,
+    tab_size: usize,
+) -> Result<(), O::Error> {
+    let mut position = from;
+    loop {
+        let next_stop = (position / tab_size + 1) * tab_size;
+        if next_stop > to {
+            break;
+        }
+        f.write_str(if next_stop - position > 1 { "\t" } else { " " })?;
+        position = next_stop;
+    }
+    if position < to {
+        f.write_str(&" ".repeat(to - position))?;
+    }
+    Ok(())
+}
+
 // Adapted from the unstable API:
 // https://doc.rust-lang.org/std/primitive.usize.html#method.div_ceil
 // Can be removed on MSRV 1.73.
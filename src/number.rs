@@ -0,0 +1,93 @@
+// For the full copyright and license information, please view the LICENSE
+// file that was distributed with this source code.
+
+//! Formatting of numbers in cells.
+
+/// A way to write the numbers in a column of a [`Table`](crate::Table), see
+/// [`TableOptions::column_formats`](crate::TableOptions::column_formats).
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum NumberFormat {
+    /// Put the given separator in between every group of three digits of the
+    /// integer part, such as `1,234,567.5` with `','`.
+    Thousands(char),
+
+    /// Write sizes in bytes with a unit, in powers of 1024, like `ls -h`
+    /// does, such as `1.5K` or `12M`. Sizes are rounded up, with a single
+    /// decimal if they are below 10, and sizes below 1024 are kept as they
+    /// are. This is only done for whole numbers of bytes.
+    HumanSize,
+
+    /// Round numbers to the given number of decimals, such as `3.14` with
+    /// 2 decimals.
+    Decimals(usize),
+}
+
+impl NumberFormat {
+    /// Formats the number in the given text, or returns `None` if the text
+    /// is not a number this format applies to.
+    ///
+    /// ```
+    /// use term_grid::NumberFormat;
+    ///
+    /// assert_eq!(NumberFormat::Thousands(',').format("-1234567.5"), Some("-1,234,567.5".into()));
+    /// assert_eq!(NumberFormat::HumanSize.format("1536"), Some("1.5K".into()));
+    /// assert_eq!(NumberFormat::Decimals(2).format("3.14159"), Some("3.14".into()));
+    /// assert_eq!(NumberFormat::Decimals(2).format("n/a"), None);
+    /// ```
+    pub fn format(&self, text: &str) -> Option<String> {
+        let text = text.trim();
+        match *self {
+            NumberFormat::Thousands(separator) => {
+                text.parse::<f64>().ok()?;
+                let (sign, digits) = match text.strip_prefix(['-', '+']) {
+                    Some(digits) => text.split_at(text.len() - digits.len()),
+                    None => ("", text),
+                };
+                let (integer, fraction) = digits.split_at(digits.find('.').unwrap_or(digits.len()));
+                if !integer.chars().all(|c| c.is_ascii_digit()) {
+                    return None;
+                }
+                let mut formatted = String::from(sign);
+                for (i, digit) in integer.chars().enumerate() {
+                    if i > 0 && (integer.len() - i) % 3 == 0 {
+                        formatted.push(separator);
+                    }
+                    formatted.push(digit);
+                }
+                formatted.push_str(fraction);
+                Some(formatted)
+            }
+            NumberFormat::HumanSize => {
+                let size = text.parse::<u64>().ok()?;
+                let mut unit = 0;
+                let mut value = size as f64;
+                while value >= 1024.0 && unit < UNITS.len() {
+                    value /= 1024.0;
+                    unit += 1;
+                }
+                if unit == 0 {
+                    return Some(size.to_string());
+                }
+                // Rounding up can make a whole unit of the next size.
+                if value.ceil() >= 1024.0 && unit < UNITS.len() {
+                    value /= 1024.0;
+                    unit += 1;
+                }
+                let unit = UNITS[unit - 1];
+                let tenths = (value * 10.0).ceil();
+                if tenths < 100.0 {
+                    Some(format!("{:.1}{unit}", tenths / 10.0))
+                } else {
+                    Some(format!("{}{unit}", value.ceil()))
+                }
+            }
+            NumberFormat::Decimals(decimals) => {
+                let number = text.parse::<f64>().ok().filter(|n| n.is_finite())?;
+                Some(format!("{number:.decimals$}"))
+            }
+        }
+    }
+}
+
+/// The units of [`NumberFormat::HumanSize`], for each power of 1024.
+const UNITS: [&str; 6] = ["K", "M", "G", "T", "P", "E"];
@@ -0,0 +1,147 @@
+// For the full copyright and license information, please view the LICENSE
+// file that was distributed with this source code.
+
+//! Grids of different groups of cells, displayed side by side.
+
+use std::fmt;
+
+use crate::{FmtOutput, Grid, GridCell, GridOptions, LineOutput, Output};
+
+/// A group of cells that is laid out in its own share of the width, see
+/// [`Panes`].
+#[derive(Debug)]
+pub struct Pane<T> {
+    /// The cells of the group
+    pub cells: Vec<T>,
+
+    /// The share of the width that the group gets, relative to the shares of
+    /// the other groups
+    ///
+    /// Giving two groups shares of 70 and 30 splits the width 70% to 30%.
+    pub share: usize,
+}
+
+/// Groups of cells that are laid out separately, each in its own share of
+/// the width, and displayed side by side, such as a listing of names next to
+/// a listing of annotations.
+///
+/// Every group is laid out as a [`Grid`] with the same options, except for
+/// the width. The groups are separated by as many spaces as the filling is
+/// wide, and their lines are indented and terminated together, like those of
+/// a single grid.
+///
+/// ```
+/// use term_grid::{GridOptions, Pane, Panes};
+///
+/// let panes = Panes::new(
+///     vec![
+///         Pane { cells: vec!["one", "two", "three", "four"], share: 2 },
+///         Pane { cells: vec!["a", "b"], share: 1 },
+///     ],
+///     GridOptions { width: 30, ..Default::default() },
+/// );
+/// assert_eq!(panes.to_string(), "one    two          a  b\nthree  four\n");
+/// ```
+#[derive(Debug)]
+pub struct Panes<T: GridCell> {
+    grids: Vec<Grid<T>>,
+    widths: Vec<usize>,
+    separator: String,
+    options: GridOptions,
+}
+
+impl<T: GridCell> Panes<T> {
+    /// Lays out every group of cells in its share of the width.
+    pub fn new(panes: Vec<Pane<T>>, options: GridOptions) -> Self {
+        let separator_width = options.filling.width(|t| options.measure(t));
+        // The lines of the groups are indented together.
+        let available_width = options.width.saturating_sub(
+            options.measure(&options.indent) + separator_width * panes.len().saturating_sub(1),
+        );
+        let total_share = panes.iter().map(|pane| pane.share).sum::<usize>().max(1);
+
+        let mut widths = Vec::with_capacity(panes.len());
+        let grids = panes
+            .into_iter()
+            .map(|pane| {
+                let width = available_width * pane.share / total_share;
+                widths.push(width);
+                let options = GridOptions {
+                    width,
+                    indent: String::new(),
+                    ..options.clone()
+                };
+                Grid::new(pane.cells, options)
+            })
+            .collect();
+
+        Self {
+            grids,
+            widths,
+            separator: " ".repeat(separator_width),
+            options,
+        }
+    }
+
+    /// The grids of the groups, from left to right.
+    pub fn grids(&self) -> &[Grid<T>] {
+        &self.grids
+    }
+}
+
+impl<T: GridCell> fmt::Display for Panes<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // The groups are drawn without indenting or terminating their lines,
+        // which is done for the lines they make up together.
+        let mut rendered = Vec::with_capacity(self.grids.len());
+        for grid in &self.grids {
+            let mut text = String::new();
+            grid.render_either(&mut FmtOutput(&mut text), false)?;
+            rendered.push(text);
+        }
+        let lines: Vec<Vec<&str>> = rendered.iter().map(|text| text.lines().collect()).collect();
+        let num_lines = lines.iter().map(Vec::len).max().unwrap_or(0);
+
+        // A group that does not fit in its share, and falls back to a single
+        // column, is wider than that.
+        let widths: Vec<usize> = lines
+            .iter()
+            .zip(&self.grids)
+            .zip(&self.widths)
+            .map(|((pane_lines, grid), &width)| {
+                let measure = |line: &&str| grid.options.measure(line);
+                pane_lines.iter().map(measure).max().unwrap_or(0).max(width)
+            })
+            .collect();
+
+        let options = &self.options;
+        let mut out = LineOutput {
+            inner: f,
+            indent: &options.indent,
+            terminator: &options.line_terminator,
+            line_start: true,
+            pending_terminator: false,
+        };
+        for y in 0..num_lines {
+            // Only pad the groups that have something after them.
+            let last = (0..lines.len())
+                .rev()
+                .find(|&i| lines[i].get(y).is_some_and(|line| !line.is_empty()))
+                .unwrap_or(0);
+            for (i, pane_lines) in lines.iter().enumerate().take(last + 1) {
+                let line = pane_lines.get(y).copied().unwrap_or("");
+                out.write_str(line)?;
+                if i < last {
+                    let padding = widths[i].saturating_sub(self.grids[i].options.measure(line));
+                    out.write_str(&" ".repeat(padding))?;
+                    out.write_str(&self.separator)?;
+                }
+            }
+            out.write_str("\n")?;
+        }
+        if options.final_newline {
+            out.end_line()?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,884 @@
+// For the full copyright and license information, please view the LICENSE
+// file that was distributed with this source code.
+
+//! Rows of fields, aligned into columns like `column -t` does.
+
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::fmt;
+use std::io;
+
+use crate::{
+    Alignment, ColumnWidth, Direction, Grid, GridCell, GridOptions, Mode, NumberFormat, Overflow,
+    VerticalAlignment,
+};
+
+/// What is displayed in a column of a row of a table.
+#[derive(Debug)]
+enum Field<T> {
+    /// A field of the row, along with the alignment of its column
+    Cell(T, Option<Alignment>),
+
+    /// A field of the row whose number is displayed differently, see
+    /// [`TableOptions::column_formats`]
+    Formatted(T, String, Option<Alignment>),
+
+    /// The place of a field that a shorter row is missing
+    Missing,
+
+    /// A field that is the same as the one above it, which is left blank
+    Merged(T),
+
+    /// A field in a column that is left out, which is hidden
+    Dropped(T),
+
+    /// Text added by the table itself, along with its alignment
+    Text(String, Option<Alignment>),
+
+    /// The rule under a title of the header, which is as wide as its column
+    /// once the table is laid out
+    Rule(char),
+
+    /// The title of a group of columns, along with the number of columns it
+    /// spans
+    Group(String, usize),
+}
+
+impl<T: GridCell> Field<T> {
+    fn cell(&self) -> Option<&T> {
+        match self {
+            Field::Cell(cell, _)
+            | Field::Formatted(cell, ..)
+            | Field::Merged(cell)
+            | Field::Dropped(cell) => Some(cell),
+            _ => None,
+        }
+    }
+
+    fn into_cell(self) -> Option<T> {
+        match self {
+            Field::Cell(cell, _)
+            | Field::Formatted(cell, ..)
+            | Field::Merged(cell)
+            | Field::Dropped(cell) => Some(cell),
+            _ => None,
+        }
+    }
+}
+
+impl<T: GridCell> GridCell for Field<T> {
+    fn contents(&self) -> Cow<'_, str> {
+        match self {
+            Field::Cell(cell, _) => cell.contents(),
+            Field::Formatted(_, text, _) => Cow::Borrowed(text),
+            Field::Missing | Field::Merged(_) | Field::Dropped(_) | Field::Rule(_) => {
+                Cow::Borrowed("")
+            }
+            Field::Text(text, _) | Field::Group(text, _) => Cow::Borrowed(text),
+        }
+    }
+
+    fn write(&self, f: &mut dyn fmt::Write) -> fmt::Result {
+        match self {
+            Field::Cell(cell, _) => cell.write(f),
+            Field::Formatted(_, text, _) => f.write_str(text),
+            Field::Missing | Field::Merged(_) | Field::Dropped(_) | Field::Rule(_) => Ok(()),
+            Field::Text(text, _) | Field::Group(text, _) => f.write_str(text),
+        }
+    }
+
+    fn bytes(&self) -> Option<&[u8]> {
+        match self {
+            Field::Cell(cell, _) => cell.bytes(),
+            _ => None,
+        }
+    }
+
+    fn width(&self) -> Option<usize> {
+        match self {
+            Field::Cell(cell, _) => cell.width(),
+            Field::Missing | Field::Merged(_) | Field::Dropped(_) | Field::Rule(_) => Some(0),
+            Field::Formatted(..) | Field::Text(..) | Field::Group(..) => None,
+        }
+    }
+
+    fn min_width(&self) -> Option<usize> {
+        self.cell().and_then(GridCell::min_width)
+    }
+
+    fn hidden(&self) -> bool {
+        matches!(self, Field::Dropped(_))
+    }
+
+    fn span(&self) -> usize {
+        match self {
+            Field::Group(_, span) => *span,
+            _ => 1,
+        }
+    }
+
+    fn alignment(&self) -> Option<Alignment> {
+        match self {
+            Field::Cell(cell, alignment) | Field::Formatted(cell, _, alignment) => {
+                cell.alignment().or(*alignment)
+            }
+            Field::Text(_, alignment) => *alignment,
+            _ => None,
+        }
+    }
+
+    fn vertical_alignment(&self) -> Option<VerticalAlignment> {
+        self.cell().and_then(GridCell::vertical_alignment)
+    }
+}
+
+/// Values that can be turned into rows of a [`Table`], such as records.
+///
+/// With the `derive` feature, this can be derived for structs, which makes a
+/// field of the row out of every field of the struct, in order.
+///
+/// ```
+/// use term_grid::{GridOptions, Table, TableOptions, ToRow};
+///
+/// struct Entry {
+///     name: &'static str,
+///     size: u64,
+/// }
+///
+/// impl ToRow for Entry {
+///     type Cell = String;
+///
+///     fn to_row(&self) -> Vec<String> {
+///         vec![self.name.to_string(), self.size.to_string()]
+///     }
+/// }
+///
+/// let entries = [Entry { name: "a.txt", size: 12 }, Entry { name: "b", size: 3 }];
+/// let table = Table::from_rows(&entries, GridOptions::default(), TableOptions::default());
+/// assert_eq!(table.to_string(), "a.txt  12\nb      3\n");
+/// ```
+pub trait ToRow {
+    /// The type of the fields of the row
+    type Cell: GridCell;
+
+    /// The fields of the row, one for each column, starting with the first.
+    fn to_row(&self) -> Vec<Self::Cell>;
+}
+
+impl<R: ToRow + ?Sized> ToRow for &R {
+    type Cell = R::Cell;
+
+    fn to_row(&self) -> Vec<Self::Cell> {
+        (**self).to_row()
+    }
+}
+
+impl<T: GridCell + Clone> ToRow for [T] {
+    type Cell = T;
+
+    fn to_row(&self) -> Vec<T> {
+        self.to_vec()
+    }
+}
+
+impl<T: GridCell + Clone> ToRow for Vec<T> {
+    type Cell = T;
+
+    fn to_row(&self) -> Vec<T> {
+        self.clone()
+    }
+}
+
+/// The header of a [`Table`], see [`TableOptions::header`].
+///
+/// The titles are part of the table when it is laid out, so columns are
+/// wide enough for them.
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct Header {
+    /// The titles of the columns, starting with the first, which are aligned
+    /// like the fields of their columns
+    ///
+    /// If there are none, the line of titles is left out.
+    pub titles: Vec<String>,
+
+    /// The character to draw a line with under every title, across the
+    /// width of its column, such as `'-'`, if there are titles
+    pub rule: Option<char>,
+
+    /// Groups of adjacent columns, starting with the first column, whose
+    /// titles are shown on a line above the titles of the columns
+    ///
+    /// A group with an empty title leaves its columns out of any group.
+    pub groups: Vec<ColumnGroup>,
+}
+
+/// A group of adjacent columns of a [`Table`] under a common title, such as
+/// `"Permissions"` over the columns of the user, group and other permissions,
+/// see [`Header::groups`].
+///
+/// The title spans the columns of the group, and the separators in between
+/// them. If it is wider, the last of the columns is widened.
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct ColumnGroup {
+    /// The title of the group
+    pub title: String,
+
+    /// The number of columns in the group
+    pub columns: usize,
+}
+
+/// A row after the last row of a [`Table`] that summarizes the fields of some
+/// of its columns, see [`TableOptions::totals`].
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct Totals {
+    /// The text in the first column, if that is not summarized, such as
+    /// `"Total"`
+    pub label: String,
+
+    /// How to summarize each column, starting with the first column
+    ///
+    /// Columns that are not in the list or are `None` are left blank.
+    pub columns: Vec<Option<Aggregate>>,
+}
+
+/// A way to summarize the fields of a column, see [`Totals`].
+///
+/// Fields that are not numbers are left out, and the result is written like
+/// the fields of the column, see [`TableOptions::column_formats`].
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum Aggregate {
+    /// The sum of the numbers, with as many decimals as the most precise one
+    Sum,
+
+    /// How many numbers there are
+    Count,
+
+    /// The average of the numbers, with two decimals
+    Average,
+}
+
+impl Aggregate {
+    /// Summarizes the numbers in the given fields.
+    fn apply<'a>(self, fields: impl Iterator<Item = Cow<'a, str>>) -> String {
+        let mut count = 0;
+        let mut sum = 0.0;
+        let mut decimals = 0;
+        for field in fields {
+            let text = field.trim();
+            let Some(number) = text.parse::<f64>().ok().filter(|n| n.is_finite()) else {
+                continue;
+            };
+            count += 1;
+            sum += number;
+            let fraction = text.find('.').map_or(0, |dot| text.len() - dot - 1);
+            decimals = decimals.max(fraction);
+        }
+        match self {
+            Aggregate::Sum => format!("{sum:.decimals$}"),
+            Aggregate::Count => count.to_string(),
+            Aggregate::Average if count == 0 => String::new(),
+            Aggregate::Average => format!("{:.2}", sum / f64::from(count)),
+        }
+    }
+}
+
+/// The options of a [`Table`] for its columns, which a [`Grid`] does not
+/// have, as the cells of a grid move between columns depending on the width.
+///
+/// The other options, such as the filling and the width, are those of the
+/// [`GridOptions`] that the table is created with.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct TableOptions {
+    /// The alignments of the columns, starting with the first column, such as
+    /// [`Alignment::Right`] for a column of sizes
+    ///
+    /// Columns that are not in the list use [`GridOptions::alignment`], and
+    /// cells with their own alignment keep it.
+    pub column_alignments: Vec<Alignment>,
+
+    /// The way to write the numbers in each column, starting with the first
+    /// column, such as [`NumberFormat::HumanSize`] for a column of sizes
+    ///
+    /// Fields are formatted before they are measured, and fields that are
+    /// not numbers are kept as they are. Columns that are not in the list or
+    /// are `None` are not formatted.
+    pub column_formats: Vec<Option<NumberFormat>>,
+
+    /// The priorities of the columns, starting with the first column, where
+    /// columns with higher numbers are more important
+    ///
+    /// When the table does not fit in the width, columns are left out,
+    /// starting with the lowest priority, until it fits. Of columns with the
+    /// same priority, the last one goes first. Columns that are not in the
+    /// list are never left out.
+    pub column_priorities: Vec<usize>,
+
+    /// The priorities of the columns for being cut to fit, starting with the
+    /// first column, where columns with lower numbers are cut first
+    ///
+    /// When the table does not fit in the width, the column with the lowest
+    /// priority is made narrower, down to a single character and the ellipsis
+    /// of [`Overflow::Truncate`], then the next one, until it fits. Of columns
+    /// with the same priority, the last one goes first. Columns that are not
+    /// in the list are never cut, and columns are only left out because of
+    /// [`TableOptions::column_priorities`] if cutting is not enough.
+    pub truncation_priorities: Vec<usize>,
+
+    /// The text shown in place of columns that are left out, which is `"…"`
+    /// by default
+    ///
+    /// A run of adjacent columns gets a single marker, which is a column of
+    /// its own. Nothing is shown if this is empty.
+    pub drop_marker: String,
+
+    /// The titles of the columns, shown above the rows
+    pub header: Option<Header>,
+
+    /// The columns in which a field that is the same as the one above it is
+    /// left blank, so that it seems to span the rows of its group, such as a
+    /// column of owners for a listing sorted by owner
+    ///
+    /// A field is only merged if the fields in the merged columns before it
+    /// are merged as well, so that groups can be nested.
+    pub merged_columns: Vec<usize>,
+
+    /// A row to show after the rows with the totals of some of the columns,
+    /// which is part of the layout
+    pub totals: Option<Totals>,
+}
+
+impl Default for TableOptions {
+    fn default() -> Self {
+        Self {
+            column_alignments: Vec::new(),
+            column_formats: Vec::new(),
+            column_priorities: Vec::new(),
+            truncation_priorities: Vec::new(),
+            drop_marker: "…".into(),
+            header: None,
+            merged_columns: Vec::new(),
+            totals: None,
+        }
+    }
+}
+
+/// Rows of fields, where every field is aligned into the column of its
+/// position in the row, such as records with a name, a size and a date.
+///
+/// The columns are laid out like those of a [`Grid`], with the filling,
+/// alignment and measurement of the options, but there are always as many
+/// of them as the longest row has fields, whatever the width. Rows with
+/// fewer fields are left blank at the end. The fields are written from right
+/// to left with [`Direction::RightToLeft`], and from left to right with any
+/// other direction. Fields do not span columns, and are not hidden.
+///
+/// Each column can have its own alignment, see
+/// [`TableOptions::column_alignments`] and [`TableOptions::column_formats`],
+/// columns can be left out when the table
+/// is too wide, see [`TableOptions::column_priorities`], or cut to fit, see
+/// [`TableOptions::truncation_priorities`], and the columns can
+/// have titles, see [`TableOptions::header`], and be summed up, see
+/// [`TableOptions::totals`]. With [`GridOptions::numbered`],
+/// the rows are numbered in a column before the first one, and repeated
+/// fields can be merged into the first of them, see
+/// [`TableOptions::merged_columns`].
+///
+/// ```
+/// use term_grid::{GridOptions, Table, TableOptions};
+///
+/// let table = Table::new(
+///     vec![
+///         vec!["alpha", "1", "on"],
+///         vec!["b", "200"],
+///         vec!["gamma", "30", "off"],
+///     ],
+///     GridOptions::default(),
+///     TableOptions::default(),
+/// );
+/// assert_eq!(table.to_string(), "alpha  1    on\nb      200\ngamma  30   off\n");
+/// ```
+#[derive(Debug)]
+pub struct Table<T: GridCell> {
+    grid: Grid<Field<T>>,
+    options: GridOptions,
+    table_options: TableOptions,
+    row_lengths: Vec<usize>,
+    num_rows: usize,
+    num_columns: usize,
+    dropped: Vec<usize>,
+}
+
+impl<T: GridCell> Table<T> {
+    /// Aligns the fields of the given rows into columns.
+    pub fn new(rows: Vec<Vec<T>>, options: GridOptions, table_options: TableOptions) -> Self {
+        let original_options = options.clone();
+        let row_lengths: Vec<usize> = rows.iter().map(Vec::len).collect();
+        let num_rows = rows.len();
+        let header = table_options.header.as_ref();
+        let num_fields = rows
+            .iter()
+            .map(Vec::len)
+            .chain(header.map(|header| header.titles.len()))
+            .max()
+            .unwrap_or(0);
+        // The column of numbers comes before the fields.
+        let first_field = usize::from(options.numbered && num_fields > 0);
+        let num_columns = num_fields + first_field;
+
+        let mut fields = Vec::with_capacity((num_rows + 2) * num_columns);
+        let alignment = |x: usize| table_options.column_alignments.get(x).copied();
+        let field = |x: usize, cell: T| {
+            let format = table_options.column_formats.get(x).copied().flatten();
+            match format.and_then(|format| format.format(&cell.contents())) {
+                Some(text) => Field::Formatted(cell, text, alignment(x)),
+                None => Field::Cell(cell, alignment(x)),
+            }
+        };
+        // A header can have just groups, without titles or rules.
+        let header = header.filter(|header| !header.titles.is_empty());
+        if let Some(header) = header {
+            fields.extend((0..first_field).map(|_| Field::Missing));
+            fields.extend((0..num_fields).map(|x| match header.titles.get(x) {
+                Some(title) => Field::Text(title.clone(), alignment(x)),
+                None => Field::Missing,
+            }));
+            if let Some(rule) = header.rule {
+                fields.extend((0..num_columns).map(|_| Field::Rule(rule)));
+            }
+        }
+        let has_rules = header.is_some_and(|header| header.rule.is_some());
+
+        let totals = table_options.totals.as_ref().map(|totals| {
+            (0..num_fields)
+                .map(|x| match totals.columns.get(x).copied().flatten() {
+                    Some(aggregate) => {
+                        let fields = rows.iter().filter_map(|row| Some(row.get(x)?.contents()));
+                        let text = aggregate.apply(fields);
+                        let format = table_options.column_formats.get(x).copied().flatten();
+                        let text = format.and_then(|f| f.format(&text)).unwrap_or(text);
+                        Field::Text(text, alignment(x))
+                    }
+                    None if x == 0 => Field::Text(totals.label.clone(), alignment(x)),
+                    None => Field::Missing,
+                })
+                .collect::<Vec<_>>()
+        });
+
+        // The contents of the last field of every merged column.
+        let mut merged_columns = table_options.merged_columns.clone();
+        merged_columns.sort_unstable();
+        merged_columns.dedup();
+        let mut above: Vec<Option<String>> = vec![None; merged_columns.len()];
+
+        for (y, row) in rows.into_iter().enumerate() {
+            if first_field > 0 {
+                fields.push(Field::Text((y + 1).to_string(), Some(Alignment::Right)));
+            }
+            let row_len = row.len();
+            // A field is only merged if those in the merged columns before
+            // it are, so that groups are nested.
+            let mut merging = true;
+            for (x, cell) in row.into_iter().enumerate() {
+                let Ok(i) = merged_columns.binary_search(&x) else {
+                    fields.push(field(x, cell));
+                    continue;
+                };
+                merging = merging && above[i].as_deref() == Some(&*cell.contents());
+                if merging {
+                    fields.push(Field::Merged(cell));
+                } else {
+                    above[i] = Some(cell.contents().into_owned());
+                    fields.push(field(x, cell));
+                }
+            }
+            for (i, &x) in merged_columns.iter().enumerate() {
+                if x >= row_len {
+                    above[i] = None;
+                }
+            }
+            fields.extend((row_len..num_fields).map(|_| Field::Missing));
+        }
+        if let Some(totals) = totals {
+            fields.extend((0..first_field).map(|_| Field::Missing));
+            fields.extend(totals);
+        }
+
+        let direction = match options.direction {
+            Direction::RightToLeft => Direction::RightToLeft,
+            _ => Direction::LeftToRight,
+        };
+        // Limits on the widths of the columns are for the fields.
+        let mut columns = options.columns;
+        if first_field > 0 && !columns.is_empty() {
+            columns.insert(0, ColumnWidth::default());
+        }
+        let options = GridOptions {
+            direction,
+            mode: Mode::Grid,
+            numbered: false,
+            columns,
+            ..options
+        };
+        let mut grid = lay_out(fields, num_columns, options);
+
+        let priorities = &table_options.truncation_priorities;
+        grid = shrink_columns(grid, num_columns, first_field, priorities);
+        let dropped = columns_to_drop(&grid, num_columns, first_field, &table_options);
+        let drop_marker = &table_options.drop_marker;
+        if !dropped.is_empty() {
+            grid = drop_columns(grid, num_columns, &dropped, drop_marker);
+        }
+        if has_rules {
+            grid = draw_rules(grid);
+        }
+        if let Some(header) = &table_options.header {
+            if header.groups.iter().any(|group| !group.title.is_empty()) {
+                let shown = shown_columns(num_columns, &dropped, drop_marker);
+                grid = add_groups(grid, first_field, &shown, &header.groups);
+            }
+        }
+
+        Self {
+            grid,
+            options: original_options,
+            table_options,
+            row_lengths,
+            num_rows,
+            num_columns: num_fields,
+            dropped: dropped.iter().map(|x| x - first_field).collect(),
+        }
+    }
+
+    /// Aligns the fields of the rows made out of the given values into
+    /// columns, see [`ToRow`].
+    pub fn from_rows<R: ToRow<Cell = T>>(
+        values: impl IntoIterator<Item = R>,
+        options: GridOptions,
+        table_options: TableOptions,
+    ) -> Self {
+        let rows = values.into_iter().map(|value| value.to_row()).collect();
+        Self::new(rows, options, table_options)
+    }
+
+    /// Sorts the rows by their fields in the given column with the given
+    /// function, and lays them out again. Rows without a field in the column
+    /// come last, and rows with equal fields keep their order.
+    ///
+    /// ```
+    /// use term_grid::{GridOptions, Table, TableOptions};
+    ///
+    /// let mut table = Table::new(
+    ///     vec![vec!["b", "2"], vec!["c", "10"], vec!["a", "1"]],
+    ///     GridOptions::default(),
+    ///     TableOptions::default(),
+    /// );
+    /// table.sort_by_column(0, |a, b| a.cmp(b));
+    /// assert_eq!(table.to_string(), "a  1\nb  2\nc  10\n");
+    /// ```
+    pub fn sort_by_column(&mut self, index: usize, mut compare: impl FnMut(&T, &T) -> Ordering) {
+        let mut rows = self.take_rows();
+        rows.sort_by(|a, b| match (a.get(index), b.get(index)) {
+            (Some(a), Some(b)) => compare(a, b),
+            (a, b) => a.is_none().cmp(&b.is_none()),
+        });
+        *self = Self::new(rows, self.options.clone(), self.table_options.clone());
+    }
+
+    /// Takes the rows out of the table, leaving it empty.
+    fn take_rows(&mut self) -> Vec<Vec<T>> {
+        let mut cells = std::mem::take(&mut self.grid.cells)
+            .into_iter()
+            .filter_map(Field::into_cell);
+        self.row_lengths
+            .drain(..)
+            .map(|length| cells.by_ref().take(length).collect())
+            .collect()
+    }
+
+    /// The number of terminal columns the table takes up.
+    pub fn width(&self) -> usize {
+        self.grid.width()
+    }
+
+    /// The number of rows of the table, not counting the header.
+    pub fn row_count(&self) -> usize {
+        self.num_rows
+    }
+
+    /// The number of columns of the table, which is the number of fields in
+    /// the longest row, including the columns that are left out.
+    pub fn column_count(&self) -> usize {
+        self.num_columns
+    }
+
+    /// Writes the table to the given writer, see [`Grid::write_to`].
+    pub fn write_to<W: io::Write + ?Sized>(&self, w: &mut W) -> io::Result<()> {
+        self.grid.write_to(w)
+    }
+
+    /// Writes the table to the given string or other [`fmt::Write`], see
+    /// [`Grid::render_into`].
+    pub fn render_into<W: fmt::Write>(&self, w: &mut W) -> fmt::Result {
+        self.grid.render_into(w)
+    }
+
+    /// The columns that are left out because the table is too wide, in
+    /// order, see [`TableOptions::column_priorities`].
+    pub fn dropped_columns(&self) -> &[usize] {
+        &self.dropped
+    }
+}
+
+/// Lays the fields out in the given number of columns.
+fn lay_out<T: GridCell>(
+    fields: Vec<Field<T>>,
+    num_columns: usize,
+    options: GridOptions,
+) -> Grid<Field<T>> {
+    let placeholders = fields
+        .iter()
+        .map(|field| matches!(field, Field::Missing | Field::Merged(_)))
+        .collect();
+    let mut grid = Grid::new(fields, options);
+    grid.placeholders = placeholders;
+    grid.fit_into_columns(num_columns);
+    grid
+}
+
+/// Lays the fields of the grid out again with the columns with the lowest
+/// of the given truncation priorities made narrower, as far as needed for
+/// the table to fit in its width, if there are any. The priorities are those
+/// of the fields, which start in the given column.
+fn shrink_columns<T: GridCell>(
+    grid: Grid<Field<T>>,
+    num_columns: usize,
+    first_field: usize,
+    priorities: &[usize],
+) -> Grid<Field<T>> {
+    let options = &grid.options;
+    let max_width = options.available_width();
+    if priorities.is_empty() || grid.width() < max_width {
+        return grid;
+    }
+
+    // Columns keep at least one character besides the ellipsis.
+    let min_width = match &options.overflow {
+        Overflow::Truncate(ellipsis) => options.measure(ellipsis) + 1,
+        _ => 1,
+    };
+    let priority = |x: usize| priorities.get(x.checked_sub(first_field)?);
+    let mut order: Vec<usize> = (0..num_columns)
+        .filter(|&x| priority(x).is_some())
+        .collect();
+    order.sort_by_key(|&x| (priority(x), usize::MAX - x));
+
+    let mut columns = options.columns.clone();
+    columns.resize(num_columns.max(columns.len()), ColumnWidth::default());
+    let mut excess = grid.width() + 1 - max_width;
+    for x in order {
+        let width = grid.dimensions().widths[x];
+        let narrower = excess.min(width.saturating_sub(min_width));
+        if narrower > 0 {
+            columns[x].max = Some(width - narrower);
+            columns[x].min = columns[x].min.map(|min| min.min(width - narrower));
+            excess -= narrower;
+        }
+        if excess == 0 {
+            break;
+        }
+    }
+
+    let Grid { cells, options, .. } = grid;
+    let options = GridOptions { columns, ..options };
+    lay_out(cells, num_columns, options)
+}
+
+/// The columns with the lowest priorities that have to be left out for the
+/// table to fit in its width, if it has priorities. The priorities are those
+/// of the fields, which start in the given column.
+fn columns_to_drop<T: GridCell>(
+    grid: &Grid<Field<T>>,
+    num_columns: usize,
+    first_field: usize,
+    table_options: &TableOptions,
+) -> Vec<usize> {
+    let options = &grid.options;
+    let priorities = &table_options.column_priorities;
+    let max_width = options.available_width();
+    if priorities.is_empty() || grid.width() < max_width {
+        return Vec::new();
+    }
+
+    let widths = &grid.dimensions().widths;
+    let marker_width = options.measure(&table_options.drop_marker);
+    let measure = |t: &str| options.measure(t);
+    let mut kept = vec![true; num_columns];
+    let mut dropped = Vec::new();
+    loop {
+        // A run of columns that are left out is replaced by a marker, unless
+        // that is empty.
+        let shown: Vec<usize> = (0..num_columns)
+            .filter_map(|x| match kept[x] {
+                true => Some(widths[x]),
+                false if marker_width > 0 && (x == 0 || kept[x - 1]) => Some(marker_width),
+                false => None,
+            })
+            .collect();
+        let separators: usize = (0..shown.len().saturating_sub(1))
+            .map(|x| options.filling.gap(x).width(measure))
+            .sum();
+        if shown.iter().sum::<usize>() + separators < max_width {
+            break;
+        }
+
+        // The last of the columns with the lowest priority goes first.
+        let priority = |x: usize| priorities.get(x.checked_sub(first_field)?);
+        let next = (0..num_columns)
+            .filter(|&x| kept[x] && priority(x).is_some())
+            .min_by_key(|&x| (priority(x), usize::MAX - x));
+        let Some(x) = next else { break };
+        kept[x] = false;
+        dropped.push(x);
+    }
+    dropped.sort_unstable();
+    dropped
+}
+
+/// Lays the fields of the grid out again without the given columns, with the
+/// given marker in place of every run of them.
+fn drop_columns<T: GridCell>(
+    grid: Grid<Field<T>>,
+    num_columns: usize,
+    dropped: &[usize],
+    drop_marker: &str,
+) -> Grid<Field<T>> {
+    let Grid { cells, options, .. } = grid;
+    let is_dropped = |x: usize| dropped.binary_search(&x).is_ok();
+    let marks =
+        |x: usize| is_dropped(x) && (x == 0 || !is_dropped(x - 1)) && !drop_marker.is_empty();
+
+    let mut fields = Vec::with_capacity(cells.len());
+    for (x, field) in (0..num_columns).cycle().zip(cells) {
+        if marks(x) {
+            // The rules of the header go under the marker too.
+            fields.push(match field {
+                Field::Rule(rule) => Field::Rule(rule),
+                _ => Field::Text(drop_marker.to_string(), None),
+            });
+        }
+        if !is_dropped(x) {
+            fields.push(field);
+        } else if let Some(cell) = field.into_cell() {
+            // The fields are kept, so the rows can be laid out again.
+            fields.push(Field::Dropped(cell));
+        }
+    }
+
+    // The limits of the columns move along with them.
+    let columns = (0..num_columns)
+        .filter(|&x| marks(x) || !is_dropped(x))
+        .map(|x| match is_dropped(x) {
+            true => ColumnWidth::default(),
+            false => options.columns.get(x).copied().unwrap_or_default(),
+        })
+        .collect();
+    let num_shown = (0..num_columns)
+        .filter(|&x| marks(x) || !is_dropped(x))
+        .count();
+    let options = GridOptions { columns, ..options };
+    lay_out(fields, num_shown, options)
+}
+
+/// The column each column of the table ends up in once the given columns are
+/// left out, if it is shown, as a marker for a run of columns or otherwise.
+fn shown_columns(num_columns: usize, dropped: &[usize], drop_marker: &str) -> Vec<Option<usize>> {
+    let is_dropped = |x: usize| dropped.binary_search(&x).is_ok();
+    let mut next = 0;
+    (0..num_columns)
+        .map(|x| {
+            let marks = x == 0 || !is_dropped(x - 1);
+            let shown = !is_dropped(x) || (marks && !drop_marker.is_empty());
+            shown.then(|| {
+                next += 1;
+                next - 1
+            })
+        })
+        .collect()
+}
+
+/// Lays the fields of the grid out again with a line of the titles of the
+/// given groups of columns before them. The groups start with the field in
+/// the given column, and `shown` gives the column each column ends up in.
+fn add_groups<T: GridCell>(
+    grid: Grid<Field<T>>,
+    first_field: usize,
+    shown: &[Option<usize>],
+    groups: &[ColumnGroup],
+) -> Grid<Field<T>> {
+    let num_columns = grid.dimensions().widths.len();
+    let Grid { cells, options, .. } = grid;
+
+    let mut fields = Vec::with_capacity(cells.len() + num_columns);
+    let mut next_field = first_field;
+    let mut next_column = 0;
+    for group in groups {
+        // The group spans the columns its fields end up in, if any.
+        let end = (next_field + group.columns).min(shown.len());
+        let columns: Vec<usize> = shown[next_field.min(end)..end]
+            .iter()
+            .flatten()
+            .copied()
+            .collect();
+        next_field = end;
+        let (Some(&first), Some(&last)) = (columns.first(), columns.last()) else {
+            continue;
+        };
+        if group.title.is_empty() {
+            continue;
+        }
+        fields.extend((next_column..first).map(|_| Field::Missing));
+        fields.push(Field::Group(group.title.clone(), last + 1 - first));
+        next_column = last + 1;
+    }
+    fields.extend((next_column..num_columns).map(|_| Field::Missing));
+    fields.extend(cells);
+    lay_out(fields, num_columns, options)
+}
+
+/// Lays the fields of the grid out again with the rules of the header drawn
+/// across their columns, which are as wide as before.
+fn draw_rules<T: GridCell>(grid: Grid<Field<T>>) -> Grid<Field<T>> {
+    let num_columns = grid.dimensions().widths.len();
+    let widths = grid.dimensions().widths.clone();
+    let Grid { cells, options, .. } = grid;
+
+    let mut fields = Vec::with_capacity(cells.len());
+    let mut x = 0;
+    for field in cells {
+        // Hidden fields do not take up a column.
+        let next_x = if field.hidden() {
+            x
+        } else {
+            (x + 1) % num_columns
+        };
+        fields.push(match field {
+            Field::Rule(rule) => {
+                let rule_width = options.measure(rule.encode_utf8(&mut [0; 4])).max(1);
+                let text = rule.to_string().repeat(widths[x] / rule_width);
+                Field::Text(text, None)
+            }
+            field => field,
+        });
+        x = next_x;
+    }
+    lay_out(fields, num_columns, options)
+}
+
+/// Displays the table like a grid, but always with the width in the options,
+/// as the columns are not laid out like those of a grid.
+impl<T: GridCell> fmt::Display for Table<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let boxed = f.alternate();
+        self.grid.render_lines(f, boxed)
+    }
+}
@@ -0,0 +1,246 @@
+// For the full copyright and license information, please view the LICENSE
+// file that was distributed with this source code.
+
+//! Rewriting of cell contents before they are measured and displayed.
+
+use std::iter::Peekable;
+
+use unicode_width::UnicodeWidthChar;
+
+use crate::{ansi, ControlCharacters, Quoting};
+
+const ZERO_WIDTH_JOINER: char = '\u{200d}';
+
+/// An iterator over the start and end byte indices of the grapheme clusters
+/// in a string, skipping escape sequences.
+///
+/// This is an approximation of the Unicode rules that is good enough for
+/// terminals: combining characters, emoji modifiers, characters joined by a
+/// zero width joiner and pairs of regional indicators (flags) are kept
+/// together with the character before them.
+pub struct Clusters<'a> {
+    chars: Peekable<ansi::Visible<'a>>,
+}
+
+/// Returns the grapheme clusters of the string.
+pub fn clusters(text: &str) -> Clusters<'_> {
+    Clusters {
+        chars: ansi::visible(text).peekable(),
+    }
+}
+
+fn is_regional_indicator(c: char) -> bool {
+    matches!(c, '\u{1f1e6}'..='\u{1f1ff}')
+}
+
+impl Iterator for Clusters<'_> {
+    type Item = (usize, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (start, c) = self.chars.next()?;
+        let mut end = start + c.len_utf8();
+        let mut joined = false;
+        let mut regional = is_regional_indicator(c);
+        while let Some(&(index, next)) = self.chars.peek() {
+            let extends = joined
+                || (regional && is_regional_indicator(next))
+                || matches!(next, '\u{1f3fb}'..='\u{1f3ff}')
+                || (!next.is_control() && next.width() == Some(0));
+            if !extends {
+                break;
+            }
+            joined = next == ZERO_WIDTH_JOINER;
+            regional = false;
+            end = index + next.len_utf8();
+            self.chars.next();
+        }
+        Some((start, end))
+    }
+}
+
+/// Replaces every tab in the text by spaces up to the next multiple of
+/// `tab_size`, counting from the start of the text.
+pub fn expand_tabs(text: &str, tab_size: usize, measure: impl Fn(&str) -> usize) -> String {
+    let mut expanded = String::with_capacity(text.len());
+    let mut column = 0;
+    for (index, segment) in text.split('\t').enumerate() {
+        if index > 0 && tab_size > 0 {
+            let spaces = tab_size - column % tab_size;
+            expanded.extend(std::iter::repeat(' ').take(spaces));
+            column += spaces;
+        }
+        expanded.push_str(segment);
+        column += measure(segment);
+    }
+    expanded
+}
+
+/// Replaces control characters in the text according to the given policy,
+/// except for newlines if `keep_newlines` is set.
+pub fn sanitize(text: &str, control_characters: ControlCharacters, keep_newlines: bool) -> String {
+    let mut sanitized = String::with_capacity(text.len());
+    for c in text.chars() {
+        if !c.is_ascii_control() || (keep_newlines && c == '\n') {
+            sanitized.push(c);
+            continue;
+        }
+
+        match control_characters {
+            ControlCharacters::Keep => sanitized.push(c),
+            ControlCharacters::Replace(replacement) => sanitized.push(replacement),
+            ControlCharacters::Escape => {
+                let escaped = match c {
+                    '\x07' => "\\a",
+                    '\x08' => "\\b",
+                    '\t' => "\\t",
+                    '\n' => "\\n",
+                    '\x0b' => "\\v",
+                    '\x0c' => "\\f",
+                    '\r' => "\\r",
+                    c => {
+                        sanitized.push_str(&format!("\\{:03o}", c as u32));
+                        continue;
+                    }
+                };
+                sanitized.push_str(escaped);
+            }
+        }
+    }
+    sanitized
+}
+
+/// Cuts the text so that it fits in the given width, including the ellipsis
+/// that is appended to it, and returns it along with its width. The ellipsis
+/// is left out if it does not fit by itself, or if the text already fits.
+///
+/// Grapheme clusters are never split. Escape sequences after the cut are
+/// kept, so that colors are still reset.
+pub fn truncate(
+    text: &str,
+    width: usize,
+    ellipsis: &str,
+    measure: impl Fn(&str) -> usize,
+) -> (String, usize) {
+    let text_width = measure(text);
+    if text_width <= width {
+        return (text.to_string(), text_width);
+    }
+
+    let ellipsis_width = measure(ellipsis);
+    let (budget, ellipsis, ellipsis_width) = if ellipsis_width < width {
+        (width - ellipsis_width, ellipsis, ellipsis_width)
+    } else {
+        (width, "", 0)
+    };
+
+    let mut used = 0;
+    let mut cut = text.len();
+    for (start, end) in clusters(text) {
+        let cluster_width = measure(&text[start..end]);
+        if used + cluster_width > budget {
+            cut = start;
+            break;
+        }
+        used += cluster_width;
+    }
+
+    let mut truncated = String::with_capacity(cut + ellipsis.len());
+    truncated.push_str(&text[..cut]);
+    truncated.push_str(ellipsis);
+
+    let rest = &text[cut..];
+    let mut start = 0;
+    for (index, c) in ansi::visible(rest) {
+        truncated.push_str(&rest[start..index]);
+        start = index + c.len_utf8();
+    }
+    truncated.push_str(&rest[start..]);
+
+    (truncated, used + ellipsis_width)
+}
+
+/// Breaks the text into lines that fit in the given width, indenting all
+/// lines but the first by `indent` spaces.
+pub fn wrap(text: &str, width: usize, indent: usize, measure: impl Fn(&str) -> usize) -> String {
+    let indent = indent.min(width.saturating_sub(1));
+
+    let mut buf = [0; 4];
+    let mut wrapped = String::with_capacity(text.len());
+    let mut used = 0;
+    let mut start = 0;
+    for (index, c) in ansi::visible(text) {
+        let char_width = measure(c.encode_utf8(&mut buf));
+        if used + char_width > width && used > indent {
+            wrapped.push_str(&text[start..index]);
+            wrapped.push('\n');
+            wrapped.extend(std::iter::repeat(' ').take(indent));
+            used = indent;
+            start = index;
+        }
+        used += char_width;
+    }
+    wrapped.push_str(&text[start..]);
+
+    wrapped
+}
+
+/// Characters that have a special meaning to the shell anywhere in a word.
+const SHELL_SPECIAL: &str = " \t\n!\"$&'()*;<>?[\\]^`{|}";
+
+/// Quotes the text according to the given style, or returns `None` if it
+/// stays the same.
+///
+/// Escape sequences are copied as they are, so that colors keep working.
+pub fn quote(text: &str, quoting: Quoting) -> Option<String> {
+    let needs_quotes = |text: &str| {
+        let mut visible = ansi::visible(text).map(|(_, c)| c).peekable();
+        visible.peek().is_none()
+            || visible.peek().is_some_and(|&c| c == '~' || c == '#')
+            || visible.any(|c| c.is_control() || SHELL_SPECIAL.contains(c))
+    };
+
+    match quoting {
+        Quoting::Literal => None,
+        Quoting::Shell if !needs_quotes(text) => None,
+        Quoting::Shell | Quoting::ShellAlways => {
+            // Double quotes read more naturally for names like `it's`, but
+            // they can only be used if nothing else is special within them.
+            let double = ansi::visible(text).any(|(_, c)| c == '\'')
+                && !ansi::visible(text).any(|(_, c)| "\"$`\\!".contains(c));
+            if double {
+                Some(format!("\"{text}\""))
+            } else {
+                Some(map_visible(text, '\'', |c, quoted| match c {
+                    '\'' => quoted.push_str("'\\''"),
+                    c => quoted.push(c),
+                }))
+            }
+        }
+        Quoting::C => Some(map_visible(text, '"', |c, quoted| match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            c if c.is_ascii_control() => quoted.push_str(&sanitize(
+                c.encode_utf8(&mut [0; 4]),
+                ControlCharacters::Escape,
+                false,
+            )),
+            c => quoted.push(c),
+        })),
+    }
+}
+
+/// Surrounds the text with the given quote, passing every visible character
+/// through `push`.
+fn map_visible(text: &str, quote: char, mut push: impl FnMut(char, &mut String)) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push(quote);
+    let mut start = 0;
+    for (index, c) in ansi::visible(text) {
+        quoted.push_str(&text[start..index]);
+        push(c, &mut quoted);
+        start = index + c.len_utf8();
+    }
+    quoted.push_str(&text[start..]);
+    quoted.push(quote);
+    quoted
+}
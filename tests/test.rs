@@ -3,7 +3,13 @@
 
 // spell-checker:ignore underflowed
 
-use term_grid::{Direction, Filling, Grid, GridOptions};
+use term_grid::{
+    Aggregate, Alignment, AmbiguousWidth, ByteCell, Caption, Cell, CellRef, ColumnGroup,
+    ColumnWidth, ControlCharacters, Direction, EmojiWidth, Filling, Footer, Grid, GridCell,
+    GridOptions, Header, Justify, Layout, LayoutError, LayoutReport, Mode, Newlines, NumberFormat,
+    OsCell, Overflow, Pane, Panes, Quoting, RowSeparator, Strategy, Table, TableOptions, ToRow,
+    Totals, VerticalAlignment,
+};
 
 #[test]
 fn no_items() {
@@ -13,6 +19,7 @@ fn no_items() {
             direction: Direction::TopToBottom,
             filling: Filling::Spaces(2),
             width: 40,
+            ..Default::default()
         },
     );
 
@@ -27,6 +34,7 @@ fn one_item() {
             direction: Direction::TopToBottom,
             filling: Filling::Spaces(2),
             width: 40,
+            ..Default::default()
         },
     );
     assert_eq!("1\n", grid.to_string());
@@ -40,6 +48,7 @@ fn one_item_exact_width() {
             direction: Direction::TopToBottom,
             filling: Filling::Spaces(2),
             width: 10,
+            ..Default::default()
         },
     );
 
@@ -54,6 +63,7 @@ fn one_item_just_over() {
             direction: Direction::TopToBottom,
             filling: Filling::Spaces(2),
             width: 10,
+            ..Default::default()
         },
     );
 
@@ -68,6 +78,7 @@ fn two_small_items() {
             direction: Direction::TopToBottom,
             filling: Filling::Spaces(2),
             width: 40,
+            ..Default::default()
         },
     );
 
@@ -83,6 +94,7 @@ fn two_medium_size_items() {
             direction: Direction::TopToBottom,
             filling: Filling::Spaces(2),
             width: 40,
+            ..Default::default()
         },
     );
 
@@ -101,6 +113,7 @@ fn two_big_items() {
             direction: Direction::TopToBottom,
             filling: Filling::Spaces(2),
             width: 40,
+            ..Default::default()
         },
     );
 
@@ -118,6 +131,7 @@ fn that_example_from_earlier() {
             filling: Filling::Spaces(1),
             direction: Direction::LeftToRight,
             width: 24,
+            ..Default::default()
         },
     );
 
@@ -137,6 +151,7 @@ fn number_grid_with_pipe() {
             filling: Filling::Text("|".into()),
             direction: Direction::LeftToRight,
             width: 24,
+            ..Default::default()
         },
     );
 
@@ -153,6 +168,7 @@ fn huge_separator() {
             filling: Filling::Spaces(100),
             direction: Direction::LeftToRight,
             width: 99,
+            ..Default::default()
         },
     );
     assert_eq!(grid.row_count(), 2);
@@ -166,6 +182,7 @@ fn huge_yet_unused_separator() {
             filling: Filling::Spaces(100),
             direction: Direction::LeftToRight,
             width: 99,
+            ..Default::default()
         },
     );
 
@@ -184,11 +201,45 @@ fn emoji() {
             direction: Direction::LeftToRight,
             filling: Filling::Spaces(2),
             width: 12,
+            ..Default::default()
         },
     );
     assert_eq!("🦀    hello\n👩‍🔬  hello\n", grid.to_string());
 }
 
+#[test]
+fn emoji_width_policies() {
+    for (emoji_width, expected) in [
+        (EmojiWidth::Separate, "🦀    hello\n👩‍🔬  hello\n"),
+        (EmojiWidth::Joined, "🦀  hello\n👩‍🔬  hello\n"),
+        (EmojiWidth::Narrow, "🦀  hello\n👩‍🔬   hello\n"),
+    ] {
+        let grid = Grid::new(
+            vec!["🦀", "hello", "👩‍🔬", "hello"],
+            GridOptions {
+                direction: Direction::LeftToRight,
+                filling: Filling::Spaces(2),
+                width: 12,
+                emoji_width,
+                ..Default::default()
+            },
+        );
+        assert_eq!(expected, grid.to_string());
+    }
+
+    // A zero width joiner only joins pictographs, not the letters of a
+    // Devanagari conjunct.
+    let grid = Grid::new(
+        vec!["क्\u{200d}ष", "x", "ab", "y"],
+        GridOptions {
+            width: 8,
+            emoji_width: EmojiWidth::Joined,
+            ..Default::default()
+        },
+    );
+    assert_eq!("क्\u{200d}ष  x\nab  y\n", grid.to_string());
+}
+
 // This test once underflowed, which should never happen. The test is just
 // checking that we do not get a panic.
 #[test]
@@ -201,12 +252,2924 @@ fn possible_underflow() {
             direction: Direction::TopToBottom,
             filling: Filling::Text(" | ".into()),
             width: 15,
+            ..Default::default()
         },
     );
 
     println!("{}", grid);
 }
 
+#[test]
+fn cell_alignment_override() {
+    let grid = Grid::new(
+        vec![
+            Cell::new("apple"),
+            Cell::new("5").with_alignment(Alignment::Right),
+            Cell::new("kiwi"),
+            Cell::new("120").with_alignment(Alignment::Right),
+        ],
+        GridOptions {
+            direction: Direction::LeftToRight,
+            filling: Filling::Spaces(2),
+            width: 12,
+            ..Default::default()
+        },
+    );
+
+    assert_eq!("apple    5\nkiwi   120\n", grid.to_string());
+}
+
+#[test]
+fn right_aligned() {
+    let grid = Grid::new(
+        vec!["1", "22", "333", "4444", "55", "6"],
+        GridOptions {
+            direction: Direction::TopToBottom,
+            filling: Filling::Spaces(1),
+            width: 10,
+            alignment: Alignment::Right,
+            ..Default::default()
+        },
+    );
+
+    assert_eq!("  1 4444\n 22   55\n333    6\n", grid.to_string());
+}
+
+#[test]
+fn center_aligned() {
+    let grid = Grid::new(
+        vec!["a", "bbbbb", "cc", "d"],
+        GridOptions {
+            direction: Direction::TopToBottom,
+            filling: Filling::Text("|".into()),
+            width: 20,
+            alignment: Alignment::Center,
+            ..Default::default()
+        },
+    );
+
+    assert_eq!("a|bbbbb|cc|d\n", grid.to_string());
+
+    let grid = Grid::new(
+        vec!["a", "bbbbb", "cc", "dddd"],
+        GridOptions {
+            direction: Direction::TopToBottom,
+            filling: Filling::Text("|".into()),
+            width: 12,
+            alignment: Alignment::Center,
+            ..Default::default()
+        },
+    );
+
+    assert_eq!("  a  | cc\nbbbbb|dddd\n", grid.to_string());
+}
+
+#[test]
+fn decimal_aligned() {
+    let grid = Grid::new(
+        vec!["3.14", "100", "0.5", "42.125", "7", "1000.0"],
+        GridOptions {
+            direction: Direction::TopToBottom,
+            filling: Filling::Text("|".into()),
+            width: 20,
+            alignment: Alignment::Decimal('.'),
+            ..Default::default()
+        },
+    );
+
+    assert_eq!(
+        "  3.14|  42.125\n100   |   7\n  0.5 |1000.0\n",
+        grid.to_string()
+    );
+}
+
+#[test]
+fn decimal_aligned_parts_too_wide_together() {
+    let grid = Grid::new(
+        vec!["646261", "6.2350"],
+        GridOptions {
+            width: 10,
+            alignment: Alignment::Decimal('.'),
+            ..Default::default()
+        },
+    );
+
+    assert_eq!("646261\n     6.2350\n", grid.to_string());
+}
+
+#[test]
+fn decimal_aligned_cell_with_narrower_width() {
+    let grid = Grid::new(
+        vec![Cell::new("123.45")
+            .with_width(2)
+            .with_alignment(Alignment::Decimal('.'))],
+        GridOptions::default(),
+    );
+
+    assert_eq!("123.45\n", grid.to_string());
+}
+
+#[test]
+fn decimal_aligned_split_newlines() {
+    let grid = Grid::new(
+        vec!["12345\n1.5", "2.25"],
+        GridOptions {
+            filling: Filling::Text("|".into()),
+            newlines: Newlines::Split,
+            alignment: Alignment::Decimal('.'),
+            ..Default::default()
+        },
+    );
+
+    assert_eq!("12345|2.25\n1.5  |\n", grid.to_string());
+}
+
+#[test]
+fn decimal_aligned_cells_next_to_names() {
+    let grid = Grid::new(
+        vec![
+            Cell::new("a"),
+            Cell::new("1.5 KB").with_alignment(Alignment::Decimal('.')),
+            Cell::new("bb"),
+            Cell::new("12 KB").with_alignment(Alignment::Decimal('.')),
+        ],
+        GridOptions {
+            direction: Direction::LeftToRight,
+            filling: Filling::Spaces(1),
+            width: 12,
+            ..Default::default()
+        },
+    );
+
+    assert_eq!("a   1.5 KB\nbb 12 KB\n", grid.to_string());
+}
+
+#[test]
+fn cells_with_given_width() {
+    // The markers stand in for escape codes that should not be counted.
+    let grid = Grid::new(
+        vec![
+            Cell::new("<b>one</b>").with_width(3),
+            Cell::new("two"),
+            Cell::new("three"),
+            Cell::new("<b>four</b>").with_width(4),
+        ],
+        GridOptions {
+            direction: Direction::LeftToRight,
+            filling: Filling::Spaces(1),
+            width: 11,
+            ..Default::default()
+        },
+    );
+
+    assert_eq!(grid.width(), 10);
+    assert_eq!("<b>one</b>   two\nthree <b>four</b>\n", grid.to_string());
+}
+
+#[test]
+fn colored_cells() {
+    let grid = Grid::new(
+        vec![
+            "\x1b[34mone\x1b[0m",
+            "two",
+            "three",
+            "\x1b[1;31mfour\x1b[0m",
+        ],
+        GridOptions {
+            direction: Direction::LeftToRight,
+            filling: Filling::Spaces(1),
+            width: 11,
+            ..Default::default()
+        },
+    );
+
+    assert_eq!(grid.width(), 10);
+    assert_eq!(
+        "\x1b[34mone\x1b[0m   two\nthree \x1b[1;31mfour\x1b[0m\n",
+        grid.to_string()
+    );
+}
+
+#[cfg(feature = "ansi")]
+#[test]
+fn charset_escape_sequences() {
+    let grid = Grid::new(
+        vec!["\x1b[1mone\x1b(B\x1b[m", "two"],
+        GridOptions {
+            direction: Direction::LeftToRight,
+            filling: Filling::Spaces(1),
+            width: 11,
+            ..Default::default()
+        },
+    );
+
+    assert_eq!(grid.width(), 7);
+}
+
+#[test]
+fn hyperlinks() {
+    let grid = Grid::new(
+        vec![
+            Cell::hyperlink("a.txt", "file:///tmp/a.txt"),
+            Cell::hyperlink("1.5", "https://example.com/1.5")
+                .with_alignment(Alignment::Decimal('.')),
+            Cell::hyperlink("bb.txt", "file:///tmp/bb.txt"),
+            Cell::new("22".to_string()).with_alignment(Alignment::Decimal('.')),
+        ],
+        GridOptions {
+            direction: Direction::LeftToRight,
+            filling: Filling::Spaces(1),
+            width: 12,
+            ..Default::default()
+        },
+    );
+
+    assert_eq!(grid.width(), 6 + 1 + 4);
+    assert_eq!(
+        concat!(
+            "\x1b]8;;file:///tmp/a.txt\x1b\\a.txt\x1b]8;;\x1b\\  ",
+            " \x1b]8;;https://example.com/1.5\x1b\\1.5\x1b]8;;\x1b\\\n",
+            "\x1b]8;;file:///tmp/bb.txt\x1b\\bb.txt\x1b]8;;\x1b\\ 22\n",
+        ),
+        grid.to_string()
+    );
+}
+
+#[test]
+fn custom_measure() {
+    // Count bytes instead of characters
+    let grid = Grid::new(
+        vec!["ä", "b", "c", "d"],
+        GridOptions {
+            direction: Direction::LeftToRight,
+            filling: Filling::Text("|".into()),
+            width: 5,
+            measure: Some(str::len),
+            ..Default::default()
+        },
+    );
+
+    assert_eq!(grid.width(), 4);
+    assert_eq!("ä|b\nc |d\n", grid.to_string());
+}
+
+#[test]
+fn ambiguous_width() {
+    for (ambiguous_width, expected) in [
+        (AmbiguousWidth::Narrow, "①②  ab  c\n"),
+        (AmbiguousWidth::Wide, "①②  c\nab    \n"),
+    ] {
+        let grid = Grid::new(
+            vec!["①②", "ab", "c"],
+            GridOptions {
+                direction: Direction::TopToBottom,
+                filling: Filling::Spaces(2),
+                width: 10,
+                ambiguous_width,
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(expected, grid.to_string());
+    }
+}
+
+#[test]
+fn expand_tabs() {
+    let grid = Grid::new(
+        vec!["a\tb", "c", "long\tname\tx", "d"],
+        GridOptions {
+            direction: Direction::LeftToRight,
+            filling: Filling::Text("|".into()),
+            width: 20,
+            expand_tabs: Some(4),
+            ..Default::default()
+        },
+    );
+
+    assert_eq!(grid.width(), 17 + 1 + 1);
+    assert_eq!(
+        "a   b            |c\nlong    name    x|d\n",
+        grid.to_string()
+    );
+}
+
+#[test]
+fn control_characters() {
+    for (control_characters, expected) in [
+        (ControlCharacters::Replace('?'), "a?b  c?d\nx    y\n"),
+        (ControlCharacters::Escape, "a\\nb  c\\033d\nx     y\n"),
+    ] {
+        let grid = Grid::new(
+            vec!["a\nb", "x", "c\x1bd", "y"],
+            GridOptions {
+                direction: Direction::TopToBottom,
+                filling: Filling::Spaces(2),
+                width: 13,
+                control_characters,
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(expected, grid.to_string());
+    }
+}
+
+#[test]
+fn escaped_newlines() {
+    let grid = Grid::new(
+        vec!["one\ntwo", "three"],
+        GridOptions {
+            filling: Filling::Spaces(1),
+            width: 20,
+            newlines: Newlines::Escape,
+            ..Default::default()
+        },
+    );
+
+    assert_eq!("one\\ntwo three\n", grid.to_string());
+}
+
+#[test]
+fn split_newlines() {
+    let grid = Grid::new(
+        vec!["one\ntwo", "x", "three", "a\nb\nc", "y", "z"],
+        GridOptions {
+            direction: Direction::LeftToRight,
+            filling: Filling::Text("|".into()),
+            width: 12,
+            newlines: Newlines::Split,
+            ..Default::default()
+        },
+    );
+
+    assert_eq!(grid.row_count(), 2);
+    assert_eq!(
+        "one|x|three\ntwo| |\na  |y|z\nb  | |\nc  | |\n",
+        grid.to_string()
+    );
+}
+
+#[test]
+fn vertical_alignment() {
+    let grid = Grid::new(
+        vec![
+            Cell::new("a\nb\nc\nd"),
+            Cell::new("mid").with_vertical_alignment(VerticalAlignment::Middle),
+            Cell::new("x\ny"),
+        ],
+        GridOptions {
+            filling: Filling::Text("|".into()),
+            width: 20,
+            newlines: Newlines::Split,
+            vertical_alignment: VerticalAlignment::Bottom,
+            ..Default::default()
+        },
+    );
+
+    assert_eq!("a|   |\nb|mid|\nc|   |x\nd|   |y\n", grid.to_string());
+}
+
+#[test]
+fn truncate_overwide_cells() {
+    let grid = Grid::new(
+        vec!["short", "a-very-long-name", "\x1b[1mbold-and-long\x1b[0m"],
+        GridOptions {
+            direction: Direction::TopToBottom,
+            filling: Filling::Spaces(2),
+            width: 10,
+            overflow: Overflow::Truncate("…".into()),
+            ..Default::default()
+        },
+    );
+
+    assert_eq!(grid.width(), 10);
+    assert_eq!(
+        "short\na-very-lo…\n\x1b[1mbold-and-…\x1b[0m\n",
+        grid.to_string()
+    );
+}
+
+#[test]
+fn wrap_overwide_cells() {
+    let grid = Grid::new(
+        vec!["short", "a-very-long-file-name", "x"],
+        GridOptions {
+            direction: Direction::TopToBottom,
+            filling: Filling::Spaces(2),
+            width: 10,
+            overflow: Overflow::Wrap(2),
+            ..Default::default()
+        },
+    );
+
+    assert_eq!(grid.width(), 10);
+    assert_eq!(
+        "short\na-very-lon\n  g-file-n\n  ame\nx\n",
+        grid.to_string()
+    );
+}
+
+#[test]
+fn reduced_spacing() {
+    let cells = vec!["aaaaa", "bbbbb", "ccccc", "ddddd"];
+    for (minimum_spacing, expected) in [
+        (None, "aaaaa   ccccc\nbbbbb   ddddd\n"),
+        (Some(2), "aaaaa   ccccc\nbbbbb   ddddd\n"),
+        (Some(1), "aaaaa bbbbb ccccc ddddd\n"),
+    ] {
+        let grid = Grid::new(
+            cells.clone(),
+            GridOptions {
+                direction: Direction::TopToBottom,
+                filling: Filling::Spaces(3),
+                width: 26,
+                minimum_spacing,
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(expected, grid.to_string());
+    }
+}
+
+#[test]
+fn try_new_cell_too_wide() {
+    let grid = Grid::try_new(
+        vec!["short", "1234567890!", "also too long"],
+        GridOptions {
+            width: 10,
+            ..Default::default()
+        },
+    );
+
+    assert_eq!(
+        LayoutError::CellTooWide {
+            index: 1,
+            width: 11
+        },
+        grid.unwrap_err()
+    );
+
+    let grid = Grid::try_new(
+        vec!["short", "1234567890"],
+        GridOptions {
+            width: 10,
+            ..Default::default()
+        },
+    );
+
+    assert_eq!("short\n1234567890\n", grid.unwrap().to_string());
+
+    // A single column can be wider than the width.
+    let grid = Grid::try_new(
+        vec!["short", "1234567890!"],
+        GridOptions {
+            width: 10,
+            mode: Mode::SingleColumn,
+            ..Default::default()
+        },
+    );
+
+    assert_eq!("short\n1234567890!\n", grid.unwrap().to_string());
+}
+
+#[test]
+fn try_new_invalid_options() {
+    let options = GridOptions {
+        width: 4,
+        indent: "    ".into(),
+        ..Default::default()
+    };
+    let grid = Grid::try_new(vec!["a"], options.clone());
+    assert_eq!(LayoutError::NoWidth, grid.unwrap_err());
+    assert!(Grid::try_new(Vec::<&str>::new(), options).is_ok());
+
+    let options = GridOptions::new().filling(Filling::Cycle(Vec::new()));
+    let grid = Grid::try_new(vec!["a"], options);
+    assert_eq!(LayoutError::EmptyFilling, grid.unwrap_err());
+
+    let options = GridOptions::new().filling(Filling::PerColumn(vec![
+        Filling::Spaces(1),
+        Filling::Cycle(vec![Filling::Text("\n".into())]),
+    ]));
+    let grid = Grid::try_new(vec!["a"], options);
+    assert_eq!(LayoutError::NewlineInFilling, grid.unwrap_err());
+    assert_eq!(
+        "the filling has a line break in it",
+        LayoutError::NewlineInFilling.to_string()
+    );
+}
+
+#[test]
+fn path_cells() {
+    use std::path::{Path, PathBuf};
+
+    let grid = Grid::new(
+        vec![
+            OsCell(PathBuf::from("src/lib.rs")),
+            OsCell(Path::new("README.md").to_path_buf()),
+        ],
+        GridOptions {
+            filling: Filling::Spaces(1),
+            ..Default::default()
+        },
+    );
+
+    assert_eq!("src/lib.rs README.md\n", grid.to_string());
+}
+
+#[cfg(unix)]
+#[test]
+fn non_utf8_os_cells() {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    let grid = Grid::new(
+        vec![
+            OsCell(OsStr::from_bytes(b"a\xffb")),
+            OsCell(OsStr::new("c")),
+        ],
+        GridOptions {
+            filling: Filling::Spaces(1),
+            ..Default::default()
+        },
+    );
+
+    assert_eq!(grid.width(), 5);
+    assert_eq!("a\u{fffd}b c\n", grid.to_string());
+
+    let mut bytes = Vec::new();
+    grid.write_to(&mut bytes).unwrap();
+    assert_eq!(b"a\xffb c\n", bytes.as_slice());
+}
+
+#[test]
+fn byte_cells() {
+    let grid = Grid::new(
+        vec![
+            ByteCell(&b"caf\xe9"[..]),
+            ByteCell(&b"x"[..]),
+            ByteCell(&b"\xff\xfe"[..]),
+            ByteCell(&b"yz"[..]),
+        ],
+        GridOptions {
+            direction: Direction::LeftToRight,
+            filling: Filling::Spaces(1),
+            width: 8,
+            ..Default::default()
+        },
+    );
+
+    assert_eq!("caf\u{fffd} x\n\u{fffd}\u{fffd}   yz\n", grid.to_string());
+
+    let mut bytes = Vec::new();
+    grid.write_to(&mut bytes).unwrap();
+    assert_eq!(b"caf\xe9 x\n\xff\xfe   yz\n", bytes.as_slice());
+}
+
+#[test]
+fn custom_cells() {
+    use std::fmt;
+    use term_grid::GridCell;
+
+    // Formatted when the grid is created, as its width is not known.
+    struct Size(u64);
+
+    impl GridCell for Size {
+        fn write(&self, f: &mut dyn fmt::Write) -> fmt::Result {
+            write!(f, "{}K", self.0)
+        }
+    }
+
+    // Formatted only when the grid is displayed.
+    struct Bold(&'static str);
+
+    impl GridCell for Bold {
+        fn width(&self) -> Option<usize> {
+            Some(self.0.len())
+        }
+
+        fn write(&self, f: &mut dyn fmt::Write) -> fmt::Result {
+            write!(f, "\x1b[1m{}\x1b[0m", self.0)
+        }
+    }
+
+    let grid = Grid::new(
+        vec![Size(4), Size(128), Size(16), Size(2048)],
+        GridOptions {
+            width: 12,
+            ..Default::default()
+        },
+    );
+    assert_eq!("4K   128K\n16K  2048K\n", grid.to_string());
+
+    let grid = Grid::new(
+        vec![Bold("one"), Bold("two"), Bold("three")],
+        GridOptions {
+            width: 12,
+            ..Default::default()
+        },
+    );
+    assert_eq!(
+        "\x1b[1mone\x1b[0m    \x1b[1mtwo\x1b[0m\n\x1b[1mthree\x1b[0m  \n",
+        grid.to_string()
+    );
+
+    let mut bytes = Vec::new();
+    grid.write_to(&mut bytes).unwrap();
+    assert_eq!(grid.to_string().as_bytes(), bytes.as_slice());
+}
+
+#[test]
+fn borrowed_cells() {
+    let cells = vec![
+        Cell::new("one"),
+        Cell::new("two"),
+        Cell::new("3").with_alignment(Alignment::Right),
+        Cell::new("four"),
+    ];
+    let options = || GridOptions {
+        filling: Filling::Spaces(1),
+        width: 10,
+        ..Default::default()
+    };
+
+    let grid = Grid::from_slice(&cells, options());
+    assert_eq!("one two\n  3 four\n", grid.to_string());
+
+    let grid = Grid::new(vec![CellRef(&cells[2]), CellRef(&cells[0])], options());
+    assert_eq!("3 one\n", grid.to_string());
+    assert_eq!(4, cells.len());
+}
+
+#[test]
+fn cell_positions() {
+    let cells: Vec<_> = ["one", "two", "three", "four", "five"]
+        .into_iter()
+        .enumerate()
+        .map(|(i, name)| Cell::new(name).with_data(i))
+        .collect();
+
+    let grid = Grid::new(
+        cells.clone(),
+        GridOptions {
+            direction: Direction::TopToBottom,
+            width: 20,
+            ..Default::default()
+        },
+    );
+    let positions: Vec<_> = grid
+        .positions()
+        .map(|(cell, row, column)| (cell.data, row, column))
+        .collect();
+    assert_eq!(
+        vec![(0, 0, 0), (1, 1, 0), (2, 0, 1), (3, 1, 1), (4, 0, 2)],
+        positions
+    );
+
+    let grid = Grid::new(
+        cells,
+        GridOptions {
+            direction: Direction::LeftToRight,
+            width: 20,
+            ..Default::default()
+        },
+    );
+    let positions: Vec<_> = grid
+        .positions()
+        .map(|(cell, row, column)| (cell.data, row, column))
+        .collect();
+    assert_eq!(
+        vec![(0, 0, 0), (1, 0, 1), (2, 0, 2), (3, 1, 0), (4, 1, 1)],
+        positions
+    );
+}
+
+#[test]
+fn quoting() {
+    let cells = ["plain", "with space", "it's", "a'b$c", "tab\t", "", "~home"];
+    let quoted = |quoting| -> Vec<String> {
+        let grid = Grid::new(
+            cells.to_vec(),
+            GridOptions {
+                width: 1,
+                quoting,
+                ..Default::default()
+            },
+        );
+        grid.to_string().lines().map(String::from).collect()
+    };
+
+    assert_eq!(
+        vec![
+            "plain",
+            "'with space'",
+            "\"it's\"",
+            "'a'\\''b$c'",
+            "'tab\t'",
+            "''",
+            "'~home'"
+        ],
+        quoted(Quoting::Shell)
+    );
+    assert_eq!(
+        vec![
+            "'plain'",
+            "'with space'",
+            "\"it's\"",
+            "'a'\\''b$c'",
+            "'tab\t'",
+            "''",
+            "'~home'"
+        ],
+        quoted(Quoting::ShellAlways)
+    );
+    assert_eq!(
+        vec![
+            "\"plain\"",
+            "\"with space\"",
+            "\"it's\"",
+            "\"a'b$c\"",
+            "\"tab\\t\"",
+            "\"\"",
+            "\"~home\""
+        ],
+        quoted(Quoting::C)
+    );
+
+    // Quotes are taken into account when laying out the grid.
+    let grid = Grid::new(
+        vec!["a", "b c", "d", "e"],
+        GridOptions {
+            width: 12,
+            quoting: Quoting::Shell,
+            ..Default::default()
+        },
+    );
+    assert_eq!("a  'b c'\nd  e\n", grid.to_string());
+}
+
+#[test]
+fn padding_character() {
+    let grid = Grid::new(
+        vec!["a", "bcd", "ef", "g"],
+        GridOptions {
+            direction: Direction::TopToBottom,
+            filling: Filling::Spaces(1),
+            width: 8,
+            padding: '·',
+            ..Default::default()
+        },
+    );
+    assert_eq!("a·· ef\nbcd g\n", grid.to_string());
+
+    let grid = Grid::new(
+        vec!["1", "22", "333", "4"],
+        GridOptions {
+            alignment: Alignment::Right,
+            filling: Filling::Text("|".to_string()),
+            width: 8,
+            padding: '.',
+            ..Default::default()
+        },
+    );
+    assert_eq!("..1|22\n333|.4\n", grid.to_string());
+}
+
+#[test]
+fn leader_filling() {
+    let grid = Grid::new(
+        vec![
+            Cell::new("Introduction"),
+            Cell::new("1").with_alignment(Alignment::Right),
+            Cell::new("Usage"),
+            Cell::new("12").with_alignment(Alignment::Right),
+        ],
+        GridOptions {
+            filling: Filling::Leader('.'),
+            width: 20,
+            ..Default::default()
+        },
+    );
+    assert_eq!("Introduction .. 1\nUsage ........ 12\n", grid.to_string());
+}
+
+#[test]
+fn minimum_cell_width() {
+    let grid = Grid::new(
+        vec![
+            Cell::new("a").with_min_width(6),
+            Cell::new("b"),
+            Cell::new("c"),
+        ],
+        GridOptions {
+            filling: Filling::Spaces(1),
+            width: 12,
+            ..Default::default()
+        },
+    );
+    assert_eq!("a      b c\n", grid.to_string());
+
+    // Columns stay as wide as requested, even if that takes more lines.
+    let grid = Grid::new(
+        vec![
+            Cell::new("a").with_min_width(5),
+            Cell::new("b").with_min_width(5),
+            Cell::new("c").with_min_width(5),
+        ],
+        GridOptions {
+            filling: Filling::Spaces(1),
+            width: 12,
+            ..Default::default()
+        },
+    );
+    assert_eq!("a     b\nc     \n", grid.to_string());
+}
+
+#[test]
+fn hidden_cells() {
+    let cells: Vec<_> = ["one", "two", "three", "four", "five"]
+        .into_iter()
+        .enumerate()
+        .map(|(i, name)| Cell::new(name).with_data(i).with_hidden(i % 2 == 1))
+        .collect();
+
+    let grid = Grid::new(
+        cells,
+        GridOptions {
+            width: 20,
+            ..Default::default()
+        },
+    );
+    assert_eq!("one  three  five\n", grid.to_string());
+
+    let positions: Vec<_> = grid
+        .positions()
+        .map(|(cell, row, column)| (cell.data, row, column))
+        .collect();
+    assert_eq!(vec![(0, 0, 0), (2, 0, 1), (4, 0, 2)], positions);
+
+    let grid = Grid::new(
+        vec![Cell::new("hidden").with_hidden(true)],
+        GridOptions::default(),
+    );
+    assert_eq!("", grid.to_string());
+}
+
+#[test]
+fn truncate_graphemes() {
+    assert_eq!(("abc".into(), 3), term_grid::truncate("abc", 5));
+    assert_eq!(("ab".into(), 2), term_grid::truncate("abc", 2));
+    assert_eq!(("日".into(), 2), term_grid::truncate("日本", 3));
+    assert_eq!(
+        ("a\u{308}".into(), 1),
+        term_grid::truncate("a\u{308}o\u{308}", 1)
+    );
+    assert_eq!(("🇳🇱".into(), 2), term_grid::truncate("🇳🇱🇧🇪", 3));
+    assert_eq!(("".into(), 0), term_grid::truncate("👩\u{200d}💻 code", 3));
+    assert_eq!(
+        ("\x1b[1mab\x1b[0m".into(), 2),
+        term_grid::truncate("\x1b[1mabc\x1b[0m", 2)
+    );
+}
+
+#[test]
+fn fit_into_columns() {
+    let mut grid = Grid::new(
+        vec!["one", "two", "three", "four", "five"],
+        GridOptions {
+            direction: Direction::TopToBottom,
+            width: 1,
+            ..Default::default()
+        },
+    );
+    grid.fit_into_columns(3);
+    assert_eq!("one  three  five\ntwo  four   \n", grid.to_string());
+    assert_eq!(16, grid.width());
+    assert_eq!(2, grid.row_count());
+
+    // Four columns need as many lines as three when going down.
+    grid.fit_into_columns(4);
+    assert_eq!("one  three  five\ntwo  four   \n", grid.to_string());
+    assert_eq!(16, grid.width());
+    assert_eq!(3, grid.layout().column_widths.len());
+
+    grid.fit_into_columns(10);
+    assert_eq!("one  two  three  four  five\n", grid.to_string());
+}
+
+#[test]
+fn fit_into_lines() {
+    let mut grid = Grid::new(
+        vec!["one", "two", "three", "four", "five", "six"],
+        GridOptions {
+            direction: Direction::TopToBottom,
+            width: 1,
+            ..Default::default()
+        },
+    );
+    grid.fit_into_lines(3);
+    assert_eq!("one    four\ntwo    five\nthree  six\n", grid.to_string());
+    assert_eq!(11, grid.width());
+    assert_eq!(3, grid.row_count());
+
+    grid.fit_into_lines(1);
+    assert_eq!(1, grid.row_count());
+    assert_eq!(32, grid.width());
+}
+
+#[test]
+fn max_columns() {
+    let cells = vec!["a", "b", "c", "d", "e", "f", "g"];
+    let grid = |direction, max_columns| {
+        Grid::new(
+            cells.clone(),
+            GridOptions {
+                direction,
+                width: 80,
+                max_columns,
+                ..Default::default()
+            },
+        )
+        .to_string()
+    };
+
+    assert_eq!("a  b  c  d  e  f  g\n", grid(Direction::LeftToRight, None));
+    assert_eq!(
+        "a  b  c\nd  e  f\ng  \n",
+        grid(Direction::LeftToRight, Some(3))
+    );
+    assert_eq!(
+        "a  d  g\nb  e  \nc  f  \n",
+        grid(Direction::TopToBottom, Some(3))
+    );
+    assert_eq!(
+        "a\nb\nc\nd\ne\nf\ng\n",
+        grid(Direction::TopToBottom, Some(1))
+    );
+}
+
+#[test]
+fn column_width_limits() {
+    let grid = Grid::new(
+        vec!["a", "long name", "b", "c", "d", "e"],
+        GridOptions {
+            width: 30,
+            max_columns: Some(3),
+            overflow: Overflow::Truncate("…".to_string()),
+            columns: vec![
+                ColumnWidth {
+                    min: Some(4),
+                    max: None,
+                },
+                ColumnWidth {
+                    min: None,
+                    max: Some(5),
+                },
+            ],
+            ..Default::default()
+        },
+    );
+    assert_eq!("a     long…  b\nc     d      e\n", grid.to_string());
+}
+
+#[test]
+fn equal_columns() {
+    let grid = Grid::new(
+        vec!["a", "bb", "c", "dddd", "e", "f", "g", "h", "i"],
+        GridOptions {
+            direction: Direction::TopToBottom,
+            width: 20,
+            equal_columns: true,
+            ..Default::default()
+        },
+    );
+    assert_eq!(
+        "a     dddd  g
+bb    e     h
+c     f     i
+",
+        grid.to_string()
+    );
+}
+
+#[test]
+fn bsd_strategy() {
+    let grid = |width| {
+        Grid::new(
+            vec!["a", "bb", "c", "dddd", "e", "f", "g", "h", "i"],
+            GridOptions {
+                direction: Direction::TopToBottom,
+                width,
+                strategy: Strategy::Bsd,
+                ..Default::default()
+            },
+        )
+        .to_string()
+    };
+    assert_eq!(
+        "a     dddd  g
+bb    e     h
+c     f     i
+",
+        grid(20)
+    );
+
+    // The last column needs room for a separator too.
+    assert_eq!("a     f\nbb    g\nc     h\ndddd  i\ne     \n", grid(17));
+}
+
+#[test]
+fn justify() {
+    let grid = |justify| {
+        Grid::new(
+            vec!["a", "bbbbbb", "c", "d", "e", "f"],
+            GridOptions {
+                width: 20,
+                justify: Some(justify),
+                ..Default::default()
+            },
+        )
+        .to_string()
+    };
+
+    assert_eq!(
+        "a      bbbbbb     c\nd      e          f\n",
+        grid(Justify::Even)
+    );
+    assert_eq!(
+        "a   bbbbbb        c\nd   e             f\n",
+        grid(Justify::Proportional)
+    );
+}
+
+#[test]
+fn balanced_columns() {
+    let cells: Vec<_> = (0..10)
+        .map(|i| Cell::new(i.to_string()).with_data(i))
+        .collect();
+    let grid = Grid::new(
+        cells,
+        GridOptions {
+            direction: Direction::TopToBottom,
+            width: 9,
+            strategy: Strategy::Balanced,
+            ..Default::default()
+        },
+    );
+    assert_eq!("0  4  7\n1  5  8\n2  6  9\n3  \n", grid.to_string());
+
+    let positions: Vec<_> = grid
+        .positions()
+        .map(|(cell, row, column)| (cell.data, row, column))
+        .filter(|&(data, _, _)| (3..=5).contains(&data))
+        .collect();
+    assert_eq!(vec![(3, 3, 0), (4, 0, 1), (5, 1, 1)], positions);
+}
+
+#[test]
+fn right_to_left() {
+    let cells: Vec<_> = ["one", "two", "three", "four", "five"]
+        .into_iter()
+        .enumerate()
+        .map(|(i, name)| Cell::new(name).with_data(i))
+        .collect();
+    let grid = Grid::new(
+        cells,
+        GridOptions {
+            direction: Direction::RightToLeft,
+            width: 20,
+            ..Default::default()
+        },
+    );
+    assert_eq!("three  two   one\n       five  four\n", grid.to_string());
+
+    let positions: Vec<_> = grid
+        .positions()
+        .map(|(cell, row, column)| (cell.data, row, column))
+        .collect();
+    assert_eq!(
+        vec![(0, 0, 2), (1, 0, 1), (2, 0, 0), (3, 1, 2), (4, 1, 1)],
+        positions
+    );
+}
+
+#[test]
+fn bottom_to_top() {
+    let grid = Grid::new(
+        vec!["one", "two", "three", "four", "five"],
+        GridOptions {
+            direction: Direction::BottomToTop,
+            width: 20,
+            ..Default::default()
+        },
+    );
+    assert_eq!("two  four   \none  three  five\n", grid.to_string());
+}
+
+#[test]
+fn per_column_filling() {
+    let grid = Grid::new(
+        vec!["1", "one", "a", "2", "two", "b"],
+        GridOptions {
+            filling: Filling::PerColumn(vec![Filling::Text(" │ ".to_string()), Filling::Spaces(1)]),
+            width: 12,
+            ..Default::default()
+        },
+    );
+    assert_eq!("1 │ one a\n2 │ two b\n", grid.to_string());
+    assert_eq!(9, grid.width());
+
+    // The wider separator is taken into account when fitting the grid, so
+    // this doesn't fit in two or three columns.
+    let grid = Grid::new(
+        vec!["1", "one", "a", "2", "two", "b"],
+        GridOptions {
+            filling: Filling::PerColumn(vec![Filling::Text(" │ ".to_string()), Filling::Spaces(1)]),
+            width: 9,
+            ..Default::default()
+        },
+    );
+    assert_eq!("1\none\na\n2\ntwo\nb\n", grid.to_string());
+}
+
+#[test]
+fn row_separators() {
+    let grid = Grid::new(
+        vec!["a", "b", "c", "d", "e", "f", "g", "h", "i", "j"],
+        GridOptions {
+            width: 6,
+            row_separator: Some(RowSeparator {
+                every: 2,
+                text: "-=".to_string(),
+            }),
+            ..Default::default()
+        },
+    );
+    assert_eq!(
+        "a  b\nc  d\n-=-=\ne  f\ng  h\n-=-=\ni  j\n",
+        grid.to_string()
+    );
+}
+
+#[test]
+fn cycling_filling() {
+    let grid = Grid::new(
+        vec!["a", "b", "c", "d", "e", "f", "g", "h", "i", "j", "k", "l"],
+        GridOptions {
+            filling: Filling::Cycle(vec![Filling::Text("|".to_string()), Filling::Spaces(3)]),
+            width: 12,
+            ..Default::default()
+        },
+    );
+    assert_eq!("a|b   c|d\ne|f   g|h\ni|j   k|l\n", grid.to_string());
+}
+
+#[test]
+fn box_drawing() {
+    let grid = Grid::new(
+        vec![
+            Cell::new("one"),
+            Cell::new("22").with_alignment(Alignment::Right),
+            Cell::new("three"),
+            Cell::new("4").with_alignment(Alignment::Right),
+            Cell::new("five"),
+        ],
+        GridOptions {
+            width: 12,
+            row_separator: Some(RowSeparator {
+                every: 2,
+                text: "-".to_string(),
+            }),
+            ..Default::default()
+        },
+    );
+    assert_eq!(
+        "\
+┌───────┬────┐
+│ one   │ 22 │
+│ three │  4 │
+├───────┼────┤
+│ five  │    │
+└───────┴────┘
+",
+        format!("{grid:#}")
+    );
+    assert_eq!(
+        "one    22\nthree   4\n---------\nfive   \n",
+        grid.to_string()
+    );
+}
+
+#[test]
+fn footer() {
+    let options = |footer| GridOptions {
+        direction: Direction::LeftToRight,
+        alignment: Alignment::Right,
+        width: 12,
+        footer: Some(footer),
+        ..Default::default()
+    };
+    let cells = vec!["1", "22", "333", "4", "55", "666"];
+
+    let grid = Grid::new(cells.clone(), options(Footer::Text("6 items".into())));
+    assert_eq!("1  22  333\n4  55  666\n6 items\n", grid.to_string());
+
+    let grid = Grid::new(
+        cells,
+        options(Footer::Columns(vec!["5".into(), "77".into()])),
+    );
+    assert_eq!("1  22  333\n4  55  666\n5  77\n", grid.to_string());
+    assert_eq!(
+        "┌───┬────┬─────┐\n│ 1 │ 22 │ 333 │\n│ 4 │ 55 │ 666 │\n├───┼────┼─────┤\n│ 5 │ 77 │     │\n└───┴────┴─────┘\n",
+        format!("{grid:#}")
+    );
+}
+
+#[test]
+fn title_and_caption() {
+    let grid = Grid::new(
+        vec!["one", "two", "three", "four"],
+        GridOptions {
+            width: 30,
+            title: Some(Caption {
+                text: "Numbers".into(),
+                alignment: Alignment::Center,
+            }),
+            caption: Some(Caption {
+                text: "4 items".into(),
+                alignment: Alignment::Right,
+            }),
+            ..Default::default()
+        },
+    );
+    assert_eq!(
+        "       Numbers\none  two  three  four\n              4 items\n",
+        grid.to_string()
+    );
+    assert_eq!(
+        "          Numbers\n┌─────┬─────┬───────┬──────┐\n│ one │ two │ three │ four │\n└─────┴─────┴───────┴──────┘\n                     4 items\n",
+        format!("{grid:#}")
+    );
+}
+
+#[test]
+fn indent() {
+    let grid = Grid::new(
+        vec!["one", "two", "three", "four", "five", "six"],
+        GridOptions {
+            width: 24,
+            indent: "    ".into(),
+            row_separator: Some(RowSeparator {
+                every: 1,
+                text: "-".into(),
+            }),
+            ..Default::default()
+        },
+    );
+    assert_eq!(
+        "    one   two   three\n    -----------------\n    four  five  six\n",
+        grid.to_string()
+    );
+}
+
+#[test]
+fn row_spacing() {
+    let grid = Grid::new(
+        vec!["one", "two", "three", "four"],
+        GridOptions {
+            width: 12,
+            row_spacing: 1,
+            ..Default::default()
+        },
+    );
+    assert_eq!("one    two\n\nthree  four\n", grid.to_string());
+    assert_eq!(
+        "┌───────┬──────┐\n│ one   │ two  │\n│       │      │\n│ three │ four │\n└───────┴──────┘\n",
+        format!("{grid:#}")
+    );
+}
+
+#[test]
+fn pad_last_column() {
+    let options = |filling| GridOptions {
+        direction: Direction::TopToBottom,
+        filling,
+        width: 16,
+        pad_last_column: true,
+        ..Default::default()
+    };
+    let cells = vec!["a", "bbb", "cc", "dddd", "e"];
+
+    let grid = Grid::new(cells.clone(), options(Filling::Spaces(2)));
+    assert_eq!("a    cc    e\nbbb  dddd   \n", grid.to_string());
+
+    let grid = Grid::new(cells, options(Filling::Leader('.')));
+    assert_eq!("a ... cc ... e\nbbb . dddd    \n", grid.to_string());
+}
+
+#[test]
+fn row_edges() {
+    let grid = Grid::new(
+        vec!["a", "bbb", "cc", "dddd", "e"],
+        GridOptions {
+            direction: Direction::TopToBottom,
+            width: 20,
+            row_start: "│ ".into(),
+            row_end: " │".into(),
+            ..Default::default()
+        },
+    );
+    assert_eq!(grid.width(), 12);
+    assert_eq!("│ a    cc    e │\n│ bbb  dddd    │\n", grid.to_string());
+}
+
+#[test]
+fn row_style() {
+    let grid = Grid::new(
+        vec!["one", "two", "three", "four"],
+        GridOptions {
+            width: 12,
+            row_style: Some(|y| {
+                if y % 2 == 1 {
+                    ("\x1b[47m".into(), "\x1b[0m".into())
+                } else {
+                    (String::new(), String::new())
+                }
+            }),
+            pad_last_column: true,
+            ..Default::default()
+        },
+    );
+    assert_eq!(
+        "one    two \n\x1b[47mthree  four\x1b[0m\n",
+        grid.to_string()
+    );
+}
+
+#[test]
+fn table() {
+    let rows = vec![
+        vec!["drwxr-xr-x", "2", "root", "4096", "bin"],
+        vec!["-rw-r--r--", "1", "root", "12"],
+        vec!["lrwxrwxrwx", "1", "root", "7", "lib", "->", "usr/lib"],
+    ];
+    let table = Table::new(
+        rows.clone(),
+        GridOptions::default(),
+        TableOptions::default(),
+    );
+    assert_eq!(table.row_count(), 3);
+    assert_eq!(table.column_count(), 7);
+    assert_eq!(table.width(), 43);
+    assert_eq!(
+        concat!(
+            "drwxr-xr-x  2  root  4096  bin\n",
+            "-rw-r--r--  1  root  12\n",
+            "lrwxrwxrwx  1  root  7     lib  ->  usr/lib\n",
+        ),
+        table.to_string()
+    );
+
+    // The width does not matter.
+    let options = GridOptions {
+        filling: Filling::Text(" | ".into()),
+        width: 10,
+        ..Default::default()
+    };
+    let table = Table::new(rows, options, TableOptions::default());
+    assert_eq!(
+        concat!(
+            "drwxr-xr-x | 2 | root | 4096 | bin\n",
+            "-rw-r--r-- | 1 | root | 12\n",
+            "lrwxrwxrwx | 1 | root | 7    | lib | -> | usr/lib\n",
+        ),
+        table.to_string()
+    );
+
+    let table = Table::new(
+        Vec::<Vec<&str>>::new(),
+        GridOptions::default(),
+        TableOptions::default(),
+    );
+    assert_eq!(table.column_count(), 0);
+    assert_eq!("", table.to_string());
+}
+
+#[test]
+fn table_column_alignments() {
+    let rows = vec![
+        vec![Cell::new("Cargo.toml"), Cell::new("1024"), Cell::new("x")],
+        vec![Cell::new("src"), Cell::new("96"), Cell::new("dir")],
+        vec![
+            Cell::new("LICENSE"),
+            Cell::new("7").with_alignment(Alignment::Left),
+            Cell::new("x"),
+        ],
+    ];
+    let options = TableOptions {
+        column_alignments: vec![Alignment::Left, Alignment::Right, Alignment::Center],
+        ..Default::default()
+    };
+    let table = Table::new(rows, GridOptions::default(), options);
+    assert_eq!(
+        concat!(
+            "Cargo.toml  1024   x\n",
+            "src           96  dir\n",
+            "LICENSE     7      x\n",
+        ),
+        table.to_string()
+    );
+}
+
+#[test]
+fn table_from_rows() {
+    struct Process {
+        pid: u32,
+        command: &'static str,
+    }
+
+    impl ToRow for Process {
+        type Cell = Cell<String>;
+
+        fn to_row(&self) -> Vec<Cell<String>> {
+            vec![
+                Cell::new(self.pid.to_string()).with_alignment(Alignment::Right),
+                Cell::new(self.command.to_string()),
+            ]
+        }
+    }
+
+    let processes = vec![
+        Process {
+            pid: 1,
+            command: "init",
+        },
+        Process {
+            pid: 4242,
+            command: "cargo",
+        },
+    ];
+    let table = Table::from_rows(&processes, GridOptions::default(), TableOptions::default());
+    assert_eq!("   1  init\n4242  cargo\n", table.to_string());
+
+    let table = Table::from_rows(
+        vec![vec!["a", "b"], vec!["cc"]],
+        GridOptions::default(),
+        TableOptions::default(),
+    );
+    assert_eq!("a   b\ncc\n", table.to_string());
+}
+
+#[cfg(feature = "derive")]
+#[test]
+fn table_derived_rows() {
+    #[derive(ToRow)]
+    struct File {
+        name: String,
+        pub size: Box<u64>,
+    }
+
+    #[derive(ToRow)]
+    struct Pair(&'static str, f32);
+
+    let files = vec![
+        File {
+            name: "a.txt".into(),
+            size: Box::new(120),
+        },
+        File {
+            name: "README".into(),
+            size: Box::new(7),
+        },
+    ];
+    let table = Table::from_rows(&files, GridOptions::default(), TableOptions::default());
+    assert_eq!("a.txt   120\nREADME  7\n", table.to_string());
+
+    let table = Table::from_rows(
+        [Pair("pi", 3.5)],
+        GridOptions::default(),
+        TableOptions::default(),
+    );
+    assert_eq!("pi  3.5\n", table.to_string());
+}
+
+#[test]
+fn table_column_priorities() {
+    let rows = vec![
+        vec!["1234", "root", "20:01", "/usr/bin/daemon"],
+        vec!["56", "user", "09:30", "vim"],
+    ];
+    let options = |width| GridOptions {
+        width,
+        ..Default::default()
+    };
+    let table_options = |drop_marker: &str| TableOptions {
+        column_priorities: vec![3, 1, 1],
+        drop_marker: drop_marker.into(),
+        ..Default::default()
+    };
+
+    let table = Table::new(rows.clone(), options(40), table_options("…"));
+    assert!(table.dropped_columns().is_empty());
+    assert_eq!(
+        "1234  root  20:01  /usr/bin/daemon\n56    user  09:30  vim\n",
+        table.to_string()
+    );
+
+    // The time has the same priority as the user, but comes last.
+    let table = Table::new(rows.clone(), options(31), table_options("…"));
+    assert_eq!(table.dropped_columns(), [2]);
+    assert_eq!(
+        "1234  root  …  /usr/bin/daemon\n56    user  …  vim\n",
+        table.to_string()
+    );
+
+    let table = Table::new(rows.clone(), options(26), table_options("…"));
+    assert_eq!(table.dropped_columns(), [1, 2]);
+    assert_eq!(table.width(), 24);
+    assert_eq!(
+        "1234  …  /usr/bin/daemon\n56    …  vim\n",
+        table.to_string()
+    );
+
+    let table = Table::new(rows.clone(), options(24), table_options(""));
+    assert_eq!(table.dropped_columns(), [1, 2]);
+    assert_eq!("1234  /usr/bin/daemon\n56    vim\n", table.to_string());
+
+    // The last column has no priority, so the table overflows.
+    let table = Table::new(rows, options(10), table_options("…"));
+    assert_eq!(table.dropped_columns(), [0, 1, 2]);
+    assert_eq!("…  /usr/bin/daemon\n…  vim\n", table.to_string());
+}
+
+#[test]
+fn table_header() {
+    let rows = vec![vec!["1", "init"], vec!["4242", "cargo"]];
+    let options = |rule| TableOptions {
+        column_alignments: vec![Alignment::Right],
+        header: Some(Header {
+            titles: vec!["PID".into(), "COMMAND".into(), "TIME".into()],
+            rule,
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+
+    let table = Table::new(rows.clone(), GridOptions::default(), options(None));
+    assert_eq!(table.row_count(), 2);
+    assert_eq!(
+        concat!(" PID  COMMAND  TIME\n", "   1  init\n", "4242  cargo\n",),
+        table.to_string()
+    );
+
+    let table = Table::new(rows, GridOptions::default(), options(Some('-')));
+    assert_eq!(
+        concat!(
+            " PID  COMMAND  TIME\n",
+            "----  -------  ----\n",
+            "   1  init\n",
+            "4242  cargo\n",
+        ),
+        table.to_string()
+    );
+}
+
+#[test]
+fn numbered() {
+    let cells: Vec<String> = [
+        "one", "two", "three", "four", "five", "six", "seven", "eight",
+    ]
+    .iter()
+    .chain(&["nine", "ten"])
+    .map(|s| s.to_string())
+    .collect();
+    let grid = Grid::new(
+        cells,
+        GridOptions {
+            direction: Direction::TopToBottom,
+            width: 30,
+            numbered: true,
+            ..Default::default()
+        },
+    );
+    assert_eq!(
+        concat!(
+            " 1 one     5 five    9 nine\n",
+            " 2 two     6 six    10 ten\n",
+            " 3 three   7 seven  \n",
+            " 4 four    8 eight  \n",
+        ),
+        grid.to_string()
+    );
+
+    let rows = vec![vec!["init", "1"], vec!["cargo", "4242"]];
+    let options = GridOptions {
+        numbered: true,
+        width: 14,
+        ..Default::default()
+    };
+    let table_options = TableOptions {
+        column_priorities: vec![1, 0],
+        header: Some(Header {
+            titles: vec!["COMMAND".into(), "PID".into()],
+            rule: Some('='),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let table = Table::new(rows, options, table_options);
+    assert_eq!(table.column_count(), 2);
+    assert_eq!(table.dropped_columns(), [1]);
+    assert_eq!(
+        concat!(
+            "   COMMAND  …\n",
+            "=  =======  =\n",
+            "1  init     …\n",
+            "2  cargo    …\n",
+        ),
+        table.to_string()
+    );
+}
+
+#[test]
+fn table_merged_columns() {
+    let rows = vec![
+        vec!["root", "bin", "init"],
+        vec!["root", "bin", "sshd"],
+        vec!["root", "sbin", "cron"],
+        vec!["user", "bin", "vim"],
+        vec!["user", "bin"],
+        vec!["user", "bin", "cargo"],
+    ];
+    let options = TableOptions {
+        merged_columns: vec![1, 0],
+        ..Default::default()
+    };
+    let table = Table::new(rows, GridOptions::default(), options);
+    assert_eq!(
+        concat!(
+            "root  bin   init\n",
+            "            sshd\n",
+            "      sbin  cron\n",
+            "user  bin   vim\n",
+            "\n",
+            "            cargo\n",
+        ),
+        table.to_string()
+    );
+}
+
+#[test]
+fn table_column_groups() {
+    let rows = vec![
+        vec!["notes.txt", "rw-", "r--", "r--", "12"],
+        vec!["run.sh", "rwx", "r-x", "---", "2048"],
+    ];
+    let group = |title: &str, columns| ColumnGroup {
+        title: title.into(),
+        columns,
+    };
+    let options = GridOptions {
+        numbered: true,
+        ..Default::default()
+    };
+    let table_options = TableOptions {
+        header: Some(Header {
+            titles: vec![
+                "NAME".into(),
+                "USER".into(),
+                "GROUP".into(),
+                "OTHER".into(),
+                "SIZE".into(),
+            ],
+            rule: None,
+            groups: vec![group("", 1), group("PERMISSIONS", 3), group("", 1)],
+        }),
+        ..Default::default()
+    };
+    let table = Table::new(rows.clone(), options, table_options);
+    assert_eq!(
+        concat!(
+            "              PERMISSIONS\n",
+            "   NAME       USER  GROUP  OTHER  SIZE\n",
+            "1  notes.txt  rw-   r--    r--    12\n",
+            "2  run.sh     rwx   r-x    ---    2048\n",
+        ),
+        table.to_string()
+    );
+
+    // The title widens the last column of its group.
+    let options = TableOptions {
+        header: Some(Header {
+            groups: vec![group("FILE", 1), group("PERMISSION BITS", 2)],
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let table = Table::new(rows, GridOptions::default(), options);
+    assert_eq!(
+        concat!(
+            "FILE       PERMISSION BITS\n",
+            "notes.txt  rw-  r--         r--  12\n",
+            "run.sh     rwx  r-x         ---  2048\n",
+        ),
+        table.to_string()
+    );
+}
+
+#[test]
+fn table_sort_by_column() {
+    let rows = vec![
+        vec!["vim", "user", "12"],
+        vec!["init", "root"],
+        vec!["cargo", "user", "4242"],
+        vec!["sshd", "root", "300"],
+    ];
+    let options = GridOptions {
+        width: 15,
+        ..Default::default()
+    };
+    let table_options = TableOptions {
+        column_priorities: vec![1, 0, 1],
+        header: Some(Header {
+            titles: vec!["CMD".into(), "USER".into(), "PID".into()],
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let mut table = Table::new(rows, options, table_options);
+    assert_eq!(table.dropped_columns(), [1]);
+
+    // The column that is left out can still be sorted by.
+    table.sort_by_column(1, |a, b| a.cmp(b));
+    assert_eq!(
+        "CMD    …  PID\ninit   …\nsshd   …  300\nvim    …  12\ncargo  …  4242\n",
+        table.to_string()
+    );
+
+    let number = |cell: &&str| cell.parse::<u32>().unwrap();
+    table.sort_by_column(2, |a, b| number(b).cmp(&number(a)));
+    assert_eq!(
+        "CMD    …  PID\ncargo  …  4242\nsshd   …  300\nvim    …  12\ninit   …\n",
+        table.to_string()
+    );
+}
+
+#[cfg(feature = "csv")]
+#[test]
+fn table_from_csv() {
+    use term_grid::CsvError;
+
+    let csv = "id,note\r\n1,\"say \"\"hi\"\"\"\r\n\r\n2,,x\r\n3";
+    let table = Table::from_csv(csv, ',', GridOptions::default(), TableOptions::default()).unwrap();
+    assert_eq!(table.row_count(), 4);
+    assert_eq!(
+        "id  note\n1   say \"hi\"\n2             x\n3\n",
+        table.to_string()
+    );
+
+    let tsv = "a\tb c\nd\te\n";
+    let table =
+        Table::from_csv(tsv, '\t', GridOptions::default(), TableOptions::default()).unwrap();
+    assert_eq!("a  b c\nd  e\n", table.to_string());
+
+    let error = Table::from_csv(
+        "a\n\"b\nc",
+        ',',
+        GridOptions::default(),
+        TableOptions::default(),
+    )
+    .unwrap_err();
+    assert_eq!(error, CsvError { line: 2 });
+    assert_eq!(error.to_string(), "quoted field on line 2 is not closed");
+}
+
+#[test]
+fn column_width_spec() {
+    let columns = ColumnWidth::parse_list(" 6 , 3-, *, -4 ,, 2-5").unwrap();
+    let limits = |min, max| ColumnWidth { min, max };
+    assert_eq!(
+        columns,
+        [
+            limits(Some(6), Some(6)),
+            limits(Some(3), None),
+            limits(None, None),
+            limits(None, Some(4)),
+            limits(None, None),
+            limits(Some(2), Some(5)),
+        ]
+    );
+
+    let error = ColumnWidth::parse_list("1,5-2").unwrap_err();
+    assert_eq!(error.to_string(), "invalid column width: \"5-2\"");
+    assert!(ColumnWidth::parse_list("x").is_err());
+    assert!("-3-".parse::<ColumnWidth>().is_err());
+
+    let options = GridOptions {
+        columns: ColumnWidth::parse_list("6,*,-4").unwrap(),
+        overflow: Overflow::Truncate("…".into()),
+        ..Default::default()
+    };
+    let grid = Grid::new(vec!["a", "bb", "cccccc"], options.clone());
+    assert_eq!("a       bb  ccc…\n", grid.to_string());
+
+    let table = Table::new(
+        vec![vec!["name", "x", "description"]],
+        options,
+        TableOptions::default(),
+    );
+    assert_eq!("name    x  des…\n", table.to_string());
+}
+
+#[test]
+fn table_truncation_priorities() {
+    let rows = vec![
+        vec!["/home/user/projects/notes.txt", "12", "root"],
+        vec!["/etc/hosts", "1048576", "root"],
+    ];
+    let options = |width| GridOptions {
+        width,
+        overflow: Overflow::Truncate("…".into()),
+        ..Default::default()
+    };
+    let table_options = TableOptions {
+        truncation_priorities: vec![0, 1],
+        column_priorities: vec![0, 0, 0],
+        ..Default::default()
+    };
+
+    let table = Table::new(rows.clone(), options(30), table_options.clone());
+    assert_eq!(table.width(), 29);
+    assert!(table.dropped_columns().is_empty());
+    assert_eq!(
+        concat!(
+            "/home/user/pr…  12       root\n",
+            "/etc/hosts      1048576  root\n",
+        ),
+        table.to_string()
+    );
+
+    // The sizes are cut once the paths cannot be any narrower.
+    let table = Table::new(rows.clone(), options(14), table_options.clone());
+    assert_eq!("/…  12   root\n/…  10…  root\n", table.to_string());
+
+    let table = Table::new(rows, options(10), table_options);
+    assert_eq!(table.dropped_columns(), [2]);
+    assert_eq!("/…  12  …\n/…  1…  …\n", table.to_string());
+}
+
+#[test]
+fn table_column_formats() {
+    let rows = vec![
+        vec!["big.iso", "4831838208", "1234567", "0.5"],
+        vec!["notes.txt", "1000", "42", "12.345"],
+        vec!["empty", "0", "-", "-"],
+    ];
+    let options = TableOptions {
+        column_alignments: vec![Alignment::Left, Alignment::Right, Alignment::Right],
+        column_formats: vec![
+            None,
+            Some(NumberFormat::HumanSize),
+            Some(NumberFormat::Thousands('_')),
+            Some(NumberFormat::Decimals(1)),
+        ],
+        ..Default::default()
+    };
+    let mut table = Table::new(rows, GridOptions::default(), options);
+    assert_eq!(
+        concat!(
+            "big.iso    4.5G  1_234_567  0.5\n",
+            "notes.txt  1000         42  12.3\n",
+            "empty         0          -  -\n",
+        ),
+        table.to_string()
+    );
+
+    // The fields are sorted by what they were.
+    table.sort_by_column(1, |a, b| a.parse::<u64>().unwrap().cmp(&b.parse().unwrap()));
+    assert!(table
+        .to_string()
+        .ends_with("big.iso    4.5G  1_234_567  0.5\n"));
+
+    assert_eq!(NumberFormat::HumanSize.format("1023"), Some("1023".into()));
+    assert_eq!(NumberFormat::HumanSize.format("1024"), Some("1.0K".into()));
+    assert_eq!(NumberFormat::HumanSize.format("10239"), Some("10K".into()));
+    assert_eq!(NumberFormat::HumanSize.format("-1"), None);
+    // Sizes that round up to 1024 of a unit are written in the next one.
+    assert_eq!(
+        NumberFormat::HumanSize.format("1047552"),
+        Some("1023K".into())
+    );
+    assert_eq!(
+        NumberFormat::HumanSize.format("1048000"),
+        Some("1.0M".into())
+    );
+    for (power, unit) in (2..=6).zip(["M", "G", "T", "P", "E"]) {
+        let size = 1024_u64.pow(power) - 1;
+        assert_eq!(
+            NumberFormat::HumanSize.format(&size.to_string()),
+            Some(format!("1.0{unit}"))
+        );
+    }
+    assert_eq!(
+        NumberFormat::HumanSize.format(&u64::MAX.to_string()),
+        Some("16E".into())
+    );
+    assert_eq!(
+        NumberFormat::Thousands(',').format("999"),
+        Some("999".into())
+    );
+    assert_eq!(NumberFormat::Thousands(',').format("1e9"), None);
+}
+
+#[test]
+fn table_totals() {
+    let rows = vec![
+        vec!["a.txt", "1500", "0.25"],
+        vec!["b.txt", "2500", "1.5"],
+        vec!["c.txt", "-", "n/a"],
+    ];
+    let options = TableOptions {
+        column_alignments: vec![Alignment::Left, Alignment::Right, Alignment::Right],
+        column_formats: vec![None, Some(NumberFormat::Thousands(','))],
+        totals: Some(Totals {
+            label: "Total".into(),
+            columns: vec![None, Some(Aggregate::Sum), Some(Aggregate::Sum)],
+        }),
+        ..Default::default()
+    };
+    let table = Table::new(rows.clone(), GridOptions::default(), options);
+    assert_eq!(table.row_count(), 3);
+    assert_eq!(
+        concat!(
+            "a.txt  1,500  0.25\n",
+            "b.txt  2,500   1.5\n",
+            "c.txt      -   n/a\n",
+            "Total  4,000  1.75\n",
+        ),
+        table.to_string()
+    );
+
+    let options = TableOptions {
+        totals: Some(Totals {
+            label: "ignored".into(),
+            columns: vec![
+                Some(Aggregate::Count),
+                Some(Aggregate::Count),
+                Some(Aggregate::Average),
+            ],
+        }),
+        ..Default::default()
+    };
+    let table = Table::new(rows, GridOptions::default(), options);
+    assert!(table.to_string().ends_with("0      2     0.88\n"));
+}
+
+#[test]
+fn nested_grid() {
+    use std::borrow::Cow;
+
+    enum Field {
+        Text(&'static str),
+        Tags(Box<Grid<&'static str>>),
+    }
+
+    impl GridCell for Field {
+        fn contents(&self) -> Cow<'_, str> {
+            match self {
+                Field::Text(text) => Cow::Borrowed(text),
+                Field::Tags(grid) => grid.contents(),
+            }
+        }
+    }
+
+    let tags = |cells| {
+        let options = GridOptions {
+            filling: Filling::Text(", ".into()),
+            width: 16,
+            ..Default::default()
+        };
+        Field::Tags(Box::new(Grid::new(cells, options)))
+    };
+    let rows = vec![
+        vec![Field::Text("a.txt"), tags(vec!["text"]), Field::Text("12")],
+        vec![
+            Field::Text("logo.png"),
+            tags(vec!["image", "blue", "large", "old"]),
+            Field::Text("2048"),
+        ],
+    ];
+    let options = GridOptions {
+        newlines: Newlines::Split,
+        ..Default::default()
+    };
+    let table = Table::new(rows, options, TableOptions::default());
+    assert_eq!(
+        concat!(
+            "a.txt     text         12\n",
+            "logo.png  image, blue  2048\n",
+            "          large, old   \n",
+        ),
+        table.to_string()
+    );
+}
+
+#[test]
+fn add_cells() {
+    let options = GridOptions {
+        numbered: true,
+        width: 20,
+        ..Default::default()
+    };
+    let mut grid = Grid::new(Vec::new(), options);
+    for i in 0..10 {
+        grid.add(Cell::new(format!("c{i}")).with_hidden(i == 3));
+    }
+    assert_eq!(
+        concat!(
+            " 1 c0   2 c1   3 c2\n",
+            " 5 c4   6 c5   7 c6\n",
+            " 8 c7   9 c8  10 c9\n",
+        ),
+        grid.to_string()
+    );
+
+    grid.fit_into_width(40);
+    assert_eq!(2, grid.row_count());
+}
+
+#[test]
+fn options_builder() {
+    let options = GridOptions::new()
+        .width(11)
+        .top_to_bottom()
+        .spacing(1)
+        .alignment(Alignment::Right)
+        .indent("> ");
+    let grid = Grid::new(vec!["a", "bb", "ccc", "d"], options);
+    assert_eq!(">  a ccc\n> bb   d\n", grid.to_string());
+}
+
+#[test]
+fn layout() {
+    let options = GridOptions {
+        direction: Direction::TopToBottom,
+        filling: Filling::Text(" | ".into()),
+        width: 20,
+        ..Default::default()
+    };
+    let grid = Grid::new(vec!["one", "two", "three", "four", "five"], options);
+    assert_eq!(
+        Layout {
+            column_widths: vec![3, 5, 4],
+            row_count: 2,
+            gap_widths: vec![3, 3],
+        },
+        grid.layout()
+    );
+    assert_eq!(grid.width(), 18);
+}
+
+#[test]
+fn cell_position() {
+    let cells = vec![
+        Cell::new("a"),
+        Cell::new("b").with_hidden(true),
+        Cell::new("c"),
+        Cell::new("d"),
+    ];
+    let options = GridOptions {
+        direction: Direction::RightToLeft,
+        width: 5,
+        ..Default::default()
+    };
+    let grid = Grid::new(cells, options);
+    assert_eq!("c  a\n   d\n", grid.to_string());
+    assert_eq!(Some((0, 1)), grid.cell_position(0));
+    assert_eq!(None, grid.cell_position(1));
+    assert_eq!(Some((0, 0)), grid.cell_position(2));
+    assert_eq!(Some((1, 1)), grid.cell_position(3));
+    assert_eq!(None, grid.cell_position(4));
+}
+
+#[test]
+fn cell_at() {
+    let cells = vec![
+        Cell::new("wide").with_span(2),
+        Cell::new("a"),
+        Cell::new("b"),
+        Cell::new("c"),
+        Cell::new("d"),
+    ];
+    let options = GridOptions {
+        width: 9,
+        ..Default::default()
+    };
+    let grid = Grid::new(cells, options);
+    assert_eq!("wide  a\nb  c  d\n", grid.to_string());
+    assert_eq!(Some(0), grid.cell_at(0, 0).map(|(index, _)| index));
+    assert_eq!(Some(0), grid.cell_at(0, 1).map(|(index, _)| index));
+    assert_eq!(Some(1), grid.cell_at(0, 2).map(|(index, _)| index));
+    assert_eq!(Some("d"), grid.cell_at(1, 2).map(|(_, cell)| cell.contents));
+    assert_eq!(None, grid.cell_at(2, 0).map(|(index, _)| index));
+    assert_eq!(None, grid.cell_at(0, 3).map(|(index, _)| index));
+
+    let grid = Grid::new(
+        vec!["a", "b", "c"],
+        GridOptions {
+            width: 5,
+            ..Default::default()
+        },
+    );
+    assert_eq!("a  b\nc  \n", grid.to_string());
+    assert_eq!(Some((2, &"c")), grid.cell_at(1, 0));
+    assert_eq!(None, grid.cell_at(1, 1));
+}
+
+#[test]
+fn rows() {
+    let options = GridOptions {
+        direction: Direction::RightToLeft,
+        width: 11,
+        ..Default::default()
+    };
+    let grid = Grid::new(vec!["1", "2", "3", "4", "5"], options);
+    assert_eq!("3  2  1\n   5  4\n", grid.to_string());
+    let rows: Vec<Vec<&str>> = grid.rows().map(|row| row.copied().collect()).collect();
+    assert_eq!(vec![vec!["3", "2", "1"], vec!["5", "4"]], rows);
+
+    let grid = Grid::new(Vec::<&str>::new(), GridOptions::default());
+    assert_eq!(0, grid.rows().count());
+}
+
+#[test]
+fn lines() {
+    let cells: Vec<String> = (1..=12).map(|i| format!("cell {i}")).collect();
+    let options = GridOptions {
+        width: 30,
+        indent: "  ".into(),
+        row_separator: Some(RowSeparator {
+            every: 2,
+            text: "-".into(),
+        }),
+        title: Some(Caption {
+            text: "Title".into(),
+            alignment: Alignment::Center,
+        }),
+        footer: Some(Footer::Text("footer".into())),
+        ..Default::default()
+    };
+    let grid = Grid::new(cells.clone(), options);
+    let expected: Vec<String> = grid.to_string().lines().map(String::from).collect();
+    assert_eq!(expected, grid.lines().collect::<Vec<_>>());
+    assert_eq!(
+        vec!["            Title", "  cell 1   cell 2   cell 3"],
+        grid.lines().take(2).collect::<Vec<_>>()
+    );
+
+    let options = GridOptions {
+        mode: Mode::Stream(", ".into()),
+        width: 30,
+        ..Default::default()
+    };
+    let grid = Grid::new(cells, options);
+    let expected: Vec<String> = grid.to_string().lines().map(String::from).collect();
+    assert_eq!(expected, grid.lines().collect::<Vec<_>>());
+}
+
+#[test]
+#[should_panic(expected = "a cell returned an error when it was written")]
+fn lines_with_failing_cell() {
+    use std::borrow::Cow;
+    use std::fmt;
+
+    struct Failing;
+
+    impl GridCell for Failing {
+        fn contents(&self) -> Cow<'_, str> {
+            Cow::Borrowed("x")
+        }
+
+        fn write(&self, _: &mut dyn fmt::Write) -> fmt::Result {
+            Err(fmt::Error)
+        }
+    }
+
+    let grid = Grid::new(vec![Failing], GridOptions::default());
+    let mut text = String::new();
+    assert!(grid.render_into(&mut text).is_err());
+    grid.lines().for_each(drop);
+}
+
+#[test]
+fn write_to_buffered() {
+    use std::io::{BufWriter, Write};
+
+    let cells: Vec<String> = (0..1000).map(|i| i.to_string()).collect();
+    let grid = Grid::new(cells, GridOptions::default());
+    let mut bytes = Vec::new();
+    {
+        let out: &mut dyn Write = &mut BufWriter::new(&mut bytes);
+        grid.write_to(out).unwrap();
+        out.flush().unwrap();
+    }
+    assert_eq!(grid.to_string().into_bytes(), bytes);
+
+    let table = Table::new(
+        vec![vec!["a", "1"], vec!["bb", "2"]],
+        GridOptions::default(),
+        TableOptions::default(),
+    );
+    let mut bytes = Vec::new();
+    table.write_to(&mut bytes).unwrap();
+    assert_eq!(b"a   1\nbb  2\n", &bytes[..]);
+}
+
+#[test]
+fn render_into() {
+    let options = GridOptions {
+        width: 10,
+        indent: "  ".into(),
+        line_terminator: "\r\n".into(),
+        ..Default::default()
+    };
+    let grid = Grid::new(vec!["one", "two", "three"], options);
+    let mut text = String::from("> ");
+    grid.render_into(&mut text).unwrap();
+    assert_eq!(format!("> {grid}"), text);
+
+    let table = Table::new(
+        vec![vec!["a", "1"], vec!["bb", "2"]],
+        GridOptions::default(),
+        TableOptions::default(),
+    );
+    let mut text = String::new();
+    table.render_into(&mut text).unwrap();
+    assert_eq!("a   1\nbb  2\n", text);
+}
+
+#[test]
+fn collect_and_extend() {
+    let mut grid: Grid<String> = (1..=8).map(|i| format!("c{i}")).collect();
+    assert_eq!("c1  c2  c3  c4  c5  c6  c7  c8\n", grid.to_string());
+
+    grid = grid.with_options(GridOptions::new().width(25).top_to_bottom());
+    grid.extend((9..=12).map(|i| format!("c{i}")));
+    assert_eq!(
+        "c1  c3  c5  c7  c9   c11\nc2  c4  c6  c8  c10  c12\n",
+        grid.to_string()
+    );
+
+    let options = GridOptions {
+        numbered: true,
+        width: 40,
+        ..Default::default()
+    };
+    let mut grid = Grid::new(vec!["a"; 9], options);
+    grid.extend(["b", "c"]);
+    assert_eq!(
+        concat!(
+            " 1 a   2 a   3 a   4 a   5 a   6 a\n",
+            " 7 a   8 a   9 a  10 b  11 c  \n",
+        ),
+        grid.to_string()
+    );
+}
+
+#[test]
+fn edit_cells() {
+    fn check(grid: &Grid<Cell<String>>, cells: &[Cell<String>], options: &GridOptions) {
+        let fresh = Grid::new(cells.to_vec(), options.clone());
+        assert_eq!(fresh.to_string(), grid.to_string());
+        assert_eq!(fresh.layout(), grid.layout());
+    }
+
+    let options = GridOptions {
+        numbered: true,
+        width: 30,
+        ..Default::default()
+    };
+    let mut cells: Vec<Cell<String>> = (0..8).map(|i| Cell::new(format!("cell{i}"))).collect();
+    let mut grid = Grid::new(cells.clone(), options.clone());
+
+    let wide = Cell::new("a much wider cell".to_string());
+    grid.insert(2, wide.clone());
+    cells.insert(2, wide);
+    check(&grid, &cells, &options);
+
+    let hidden = Cell::new("hidden".to_string()).with_hidden(true);
+    grid.push(hidden.clone());
+    cells.push(hidden.clone());
+    grid.insert(0, Cell::new("x".into()));
+    cells.insert(0, Cell::new("x".into()));
+    check(&grid, &cells, &options);
+
+    // Cells pushed after hidden ones are laid out after them.
+    for cell in [Cell::new("after".into()), hidden, Cell::new("last".into())] {
+        grid.push(cell.clone());
+        cells.push(cell);
+        check(&grid, &cells, &options);
+    }
+
+    assert_eq!("a much wider cell", grid.remove(3).contents);
+    cells.remove(3);
+    check(&grid, &cells, &options);
+
+    let number = Cell::new("1.5".to_string()).with_alignment(Alignment::Decimal('.'));
+    assert_eq!("cell0", grid.replace(1, number.clone()).contents);
+    cells[1] = number;
+    let spanning = Cell::new("spanning".to_string()).with_span(2);
+    grid.replace(9, spanning.clone());
+    cells[9] = spanning;
+    check(&grid, &cells, &options);
+
+    while !cells.is_empty() {
+        grid.remove(0);
+        cells.remove(0);
+        check(&grid, &cells, &options);
+    }
+}
+
+#[test]
+fn set_width() {
+    let cells: Vec<String> = (1..=20).map(|i| "x".repeat(i % 7 + 1)).collect();
+    let mut grid = Grid::new(cells.clone(), GridOptions::default());
+    for width in [80, 40, 12, 3, 100] {
+        grid.set_width(width);
+        let fresh = Grid::new(cells.clone(), GridOptions::new().width(width));
+        assert_eq!(fresh.to_string(), grid.to_string());
+    }
+
+    let options = GridOptions {
+        overflow: Overflow::Truncate("…".into()),
+        width: 20,
+        ..Default::default()
+    };
+    let mut grid = Grid::new(vec!["a long cell of text", "b"], options);
+    grid.set_width(8);
+    assert_eq!("a long …\nb\n", grid.to_string());
+}
+
+#[test]
+fn lazy_layout() {
+    let cells: Vec<String> = (0..100).map(|i| "x".repeat(i % 13 + 1)).collect();
+    let options = GridOptions::new().width(60).top_to_bottom();
+
+    let grid = Grid::new(cells.clone(), options.clone());
+    let width = grid.width();
+    let row_count = grid.row_count();
+    let text = grid.to_string();
+    assert!(width < 60);
+    assert_eq!(text.lines().count(), row_count);
+
+    let mut grid = Grid::new(cells.clone(), options.clone());
+    grid.fit_into_columns(2);
+    assert_eq!(2, grid.layout().column_widths.len());
+    assert_eq!(50, grid.row_count());
+
+    let mut grid = Grid::new(cells, options);
+    grid.fit_into_lines(row_count);
+    assert_eq!(row_count, grid.row_count());
+    assert!(grid.width() <= width);
+}
+
+#[test]
+fn clone_and_compare() {
+    let options = GridOptions::new().width(20).spacing(3);
+    assert_eq!(options, options.clone());
+    assert_ne!(options, GridOptions::default());
+
+    let grid = Grid::new(vec!["one", "two", "three", "four"], options.clone());
+    let copy = grid.clone();
+    assert_eq!(grid, copy);
+    assert_eq!(grid.to_string(), copy.to_string());
+
+    // The layout is compared, whether or not it was worked out yet.
+    let fresh = Grid::new(vec!["one", "two", "three", "four"], options.clone());
+    assert_eq!(grid, fresh);
+
+    let other = Grid::new(vec!["one", "two", "three", "five"], options);
+    assert_ne!(grid, other);
+}
+
+#[test]
+fn formatter_width() {
+    let cells: Vec<String> = (1..=12).map(|i| format!("cell{i}")).collect();
+    let grid = Grid::new(cells.clone(), GridOptions::default());
+    for width in [10, 20, 40, 80] {
+        let fresh = Grid::new(cells.clone(), GridOptions::new().width(width));
+        assert_eq!(fresh.to_string(), format!("{grid:width$}"));
+        assert_eq!(format!("{fresh:#}"), format!("{grid:#width$}"));
+    }
+    // The grid itself keeps its width.
+    assert_eq!(2, grid.row_count());
+
+    let table = Table::new(
+        vec![vec!["a", "1"], vec!["bb", "2"]],
+        GridOptions::default(),
+        TableOptions::default(),
+    );
+    assert_eq!("a   1\nbb  2\n", format!("{table:3}"));
+}
+
+#[test]
+fn reserve() {
+    let cells = vec![
+        Cell::new("1.5".to_string()).with_alignment(Alignment::Decimal('.')),
+        Cell::new("hidden".to_string()).with_hidden(true),
+    ];
+    let mut grid = Grid::new(cells.clone(), GridOptions::default());
+    grid.reserve(100);
+    assert!(grid.capacity() >= 102);
+    let capacity = grid.capacity();
+
+    let mut all = cells;
+    for i in 0..100 {
+        let cell = Cell::new(format!("{i}.25")).with_alignment(Alignment::Decimal('.'));
+        grid.push(cell.clone());
+        all.push(cell);
+    }
+    assert_eq!(capacity, grid.capacity());
+    assert_eq!(Grid::new(all, GridOptions::default()), grid);
+}
+
+#[test]
+fn into_cells() {
+    let cells = vec![
+        Cell::new("wide cell").with_min_width(12),
+        Cell::new("tab\there"),
+        Cell::new("gone").with_hidden(true),
+    ];
+    let options = GridOptions {
+        expand_tabs: Some(8),
+        ..Default::default()
+    };
+    let grid = Grid::new(cells.clone(), options);
+    assert_eq!(&cells[..], grid.cells());
+    assert_eq!([9, 12, 4], grid.cell_widths());
+    assert_eq!(cells, grid.into_cells());
+}
+
+#[test]
+fn map_cells() {
+    let options = GridOptions {
+        numbered: true,
+        width: 20,
+        ..Default::default()
+    };
+    let grid = Grid::new(vec!["a", "bb", "ccc", "dddd"], options);
+    assert_eq!("1 a    2 bb\n3 ccc  4 dddd\n", grid.to_string());
+
+    let upper = grid.clone().map_keeping_widths(|cell| cell.to_uppercase());
+    assert_eq!("1 A    2 BB\n3 CCC  4 DDDD\n", upper.to_string());
+    assert_eq!(grid.layout(), upper.layout());
+
+    let short = grid.map(|cell| &cell[..1]);
+    assert_eq!("1 a  2 b  3 c  4 d\n", short.to_string());
+}
+
+#[test]
+fn retain() {
+    let cells: Vec<Cell<String>> = (0..30)
+        .map(|i| {
+            Cell::new(format!("{}.{i}", "9".repeat(i % 5)))
+                .with_alignment(Alignment::Decimal('.'))
+                .with_hidden(i % 7 == 0)
+        })
+        .collect();
+    let options = GridOptions {
+        numbered: true,
+        width: 40,
+        ..Default::default()
+    };
+    let mut grid = Grid::new(cells.clone(), options.clone());
+
+    let keep = |cell: &Cell<String>| !cell.contents.starts_with("9999");
+    grid.retain(keep);
+    let kept: Vec<_> = cells.into_iter().filter(keep).collect();
+    assert_eq!(Grid::new(kept.clone(), options.clone()), grid);
+
+    grid.retain(|cell| cell.contents.len() < 4);
+    let kept: Vec<_> = kept
+        .into_iter()
+        .filter(|cell| cell.contents.len() < 4)
+        .collect();
+    assert_eq!(Grid::new(kept, options), grid);
+}
+
+#[test]
+fn sort_cells() {
+    let cells: Vec<Cell<String, i32>> = [5, 3, 12, 1, 9, 3, 20, 7]
+        .iter()
+        .map(|&n| {
+            Cell::new(format!("{n}.5"))
+                .with_alignment(Alignment::Decimal('.'))
+                .with_hidden(n == 9)
+                .with_data(n)
+        })
+        .collect();
+    let options = GridOptions {
+        numbered: true,
+        width: 30,
+        ..Default::default()
+    };
+    let mut grid = Grid::new(cells.clone(), options.clone());
+    grid.sort_cells_by_key(|cell| cell.data);
+    let mut sorted = cells.clone();
+    sorted.sort_by_key(|cell| cell.data);
+    assert_eq!(
+        Grid::new(sorted, options.clone()).to_string(),
+        grid.to_string()
+    );
+
+    grid.sort_cells_by(|a, b| b.data.cmp(&a.data));
+    let mut sorted = cells;
+    sorted.sort_by_key(|cell| std::cmp::Reverse(cell.data));
+    assert_eq!(Grid::new(sorted, options).to_string(), grid.to_string());
+
+    let mut grid = Grid::new(vec!["b", "c", "a"], GridOptions::default());
+    grid.sort_cells();
+    assert_eq!(["a", "b", "c"], grid.cells());
+}
+
+#[test]
+fn used_width() {
+    let options = GridOptions {
+        width: 32,
+        filling: Filling::Text(" | ".into()),
+        row_start: "[".into(),
+        row_end: "]".into(),
+        ..Default::default()
+    };
+    let grid = Grid::new(vec!["alpha", "beta", "gamma", "delta"], options);
+    assert_eq!(28, grid.width());
+    assert_eq!(30, grid.used_width());
+    assert_eq!(2, grid.unused_width());
+
+    let grid = Grid::new(vec!["far too wide"], GridOptions::new().width(5));
+    assert_eq!(12, grid.used_width());
+    assert_eq!(0, grid.unused_width());
+}
+
+#[test]
+fn layout_report() {
+    let options = GridOptions {
+        direction: Direction::TopToBottom,
+        width: 16,
+        ..Default::default()
+    };
+    let grid = Grid::new(vec!["aaaa", "b", "cc", "dddd", "e"], options);
+    assert_eq!("aaaa  cc    e\nb     dddd  \n", grid.to_string());
+    assert_eq!(
+        LayoutReport {
+            empty_slots: 1,
+            padding: 5,
+            column_fill: vec![5.0 / 8.0, 0.75, 0.5],
+            single_column_fallback: false,
+        },
+        grid.layout_report()
+    );
+
+    let cells = vec![Cell::new("spanning"), Cell::new("a"), Cell::new("bcd")];
+    let grid = Grid::new(
+        cells
+            .into_iter()
+            .enumerate()
+            .map(|(i, c)| c.with_span(2 - i.min(1)))
+            .collect(),
+        GridOptions::new().width(9),
+    );
+    assert_eq!("spanning\na  bcd\n", grid.to_string());
+    let report = grid.layout_report();
+    assert_eq!(0, report.empty_slots);
+    assert_eq!(2, report.padding);
+    assert_eq!(vec![1.0, 0.8], report.column_fill);
+
+    let grid = Grid::new(vec!["far too wide", "b"], GridOptions::new().width(5));
+    assert!(grid.layout_report().single_column_fallback);
+
+    // BSD `ls` uses a single column if fewer than two fit, which is not a
+    // fallback as long as the cells fit.
+    let options = GridOptions {
+        width: 7,
+        strategy: Strategy::Bsd,
+        ..Default::default()
+    };
+    let grid = Grid::new(vec!["a", "bb", "cccc"], options.clone());
+    assert_eq!("a\nbb\ncccc\n", grid.to_string());
+    assert!(!grid.layout_report().single_column_fallback);
+    let grid = Grid::new(vec!["a", "bb", "far too wide"], options);
+    assert!(grid.layout_report().single_column_fallback);
+    let grid = Grid::single_column(vec!["a", "b"]);
+    assert!(!grid.layout_report().single_column_fallback);
+}
+
+#[test]
+fn line_terminator() {
+    let grid = Grid::new(
+        vec!["one", "two", "three", "four"],
+        GridOptions {
+            width: 12,
+            indent: "> ".into(),
+            line_terminator: "\r\n".into(),
+            ..Default::default()
+        },
+    );
+    assert_eq!("> one\r\n> two\r\n> three\r\n> four\r\n", grid.to_string());
+
+    let mut output = Vec::new();
+    grid.write_to(&mut output).unwrap();
+    assert_eq!(grid.to_string().as_bytes(), output);
+}
+
+#[test]
+fn no_final_newline() {
+    let grid = Grid::new(
+        vec!["one", "two", "three", "four"],
+        GridOptions {
+            width: 12,
+            row_spacing: 1,
+            final_newline: false,
+            ..Default::default()
+        },
+    );
+    assert_eq!("one    two\n\nthree  four", grid.to_string());
+    assert_eq!("[one    two\n\nthree  four]", format!("[{grid}]"));
+}
+
+#[test]
+fn stream_mode() {
+    let grid = Grid::new(
+        vec!["alpha", "beta", "gamma", "delta", "epsilon"],
+        GridOptions {
+            mode: Mode::Stream(", ".into()),
+            width: 20,
+            ..Default::default()
+        },
+    );
+    assert_eq!("alpha, beta, gamma,\ndelta, epsilon\n", grid.to_string());
+}
+
+#[test]
+fn single_column_mode() {
+    let grid = Grid::new(
+        vec!["one", "two", "three"],
+        GridOptions {
+            mode: Mode::SingleColumn,
+            width: 80,
+            ..Default::default()
+        },
+    );
+    assert_eq!("one\ntwo\nthree\n", grid.to_string());
+    assert_eq!(grid.row_count(), 3);
+}
+
+#[test]
+fn densest_columns() {
+    let cells = vec![
+        "aa", "bb", "ccccc", "ddd", "eeeeee", "ffffffff", "ggggggg", "hhhh",
+    ];
+    let grid = |strategy| {
+        Grid::new(
+            cells.clone(),
+            GridOptions {
+                direction: Direction::TopToBottom,
+                width: 16,
+                strategy,
+                ..Default::default()
+            },
+        )
+    };
+
+    assert_eq!(grid(Strategy::FewestLines).row_count(), 7);
+    assert_eq!(
+        "aa     eeeeee\nbb     ffffffff\nccccc  ggggggg\nddd    hhhh\n",
+        grid(Strategy::DensestColumns).to_string()
+    );
+}
+
+#[test]
+fn gnu_strategy() {
+    let grid = |cells: Vec<&str>, width, strategy| {
+        Grid::new(
+            cells,
+            GridOptions {
+                direction: Direction::TopToBottom,
+                width,
+                strategy,
+                ..Default::default()
+            },
+        )
+        .to_string()
+    };
+
+    // GNU ls counts the last column as at least 3 wide.
+    assert_eq!(
+        "aaaa  b\n",
+        grid(vec!["aaaa", "b"], 8, Strategy::FewestLines)
+    );
+    assert_eq!("aaaa\nb\n", grid(vec!["aaaa", "b"], 8, Strategy::Gnu));
+
+    let cells = vec!["one", "two", "three", "four", "five", "six", "seven"];
+    assert_eq!(
+        "one    four  seven\ntwo    five\nthree  six\n",
+        grid(cells, 20, Strategy::Gnu)
+    );
+}
+
+#[test]
+fn tab_stops() {
+    let grid = |strategy| {
+        Grid::new(
+            vec!["one", "two", "three", "four", "elevenchars", "six"],
+            GridOptions {
+                direction: Direction::TopToBottom,
+                width: 40,
+                tab_stops: Some(8),
+                strategy,
+                ..Default::default()
+            },
+        )
+        .to_string()
+    };
+
+    assert_eq!(
+        "one     three   elevenchars\ntwo     four    six\n",
+        grid(Strategy::FewestLines)
+    );
+    assert_eq!(
+        "one             four\ntwo             elevenchars\nthree           six\n",
+        grid(Strategy::Bsd)
+    );
+}
+
+#[test]
+fn padding_tabs() {
+    let grid = Grid::new(
+        vec!["one", "two", "three", "four"],
+        GridOptions {
+            width: 40,
+            tab_stops: Some(8),
+            padding_tabs: Some(8),
+            ..Default::default()
+        },
+    );
+    assert_eq!("one\ttwo\tthree\tfour\n", grid.to_string());
+
+    let grid = Grid::new(
+        vec!["a", "x", "bbbbbbbbbbbbbbbbbb", "y"],
+        GridOptions {
+            width: 40,
+            max_columns: Some(2),
+            padding_tabs: Some(8),
+            ..Default::default()
+        },
+    );
+    assert_eq!("a\t\t    x\nbbbbbbbbbbbbbbbbbb  y\n", grid.to_string());
+}
+
+#[test]
+fn panes() {
+    let panes = Panes::new(
+        vec![
+            Pane {
+                cells: vec!["one", "two"],
+                share: 1,
+            },
+            Pane {
+                cells: vec!["alpha", "beta", "gamma", "delta"],
+                share: 1,
+            },
+        ],
+        GridOptions {
+            width: 28,
+            ..Default::default()
+        },
+    );
+    assert_eq!(panes.grids()[1].row_count(), 2);
+    assert_eq!(
+        "one  two       alpha  beta\n               gamma  delta\n",
+        panes.to_string()
+    );
+
+    // The lines are indented and terminated like those of a grid, and a group
+    // that falls back to a single column keeps the others lined up.
+    let panes = Panes::new(
+        vec![
+            Pane {
+                cells: vec!["verylongname", "b"],
+                share: 1,
+            },
+            Pane {
+                cells: vec!["xxx", "yyy"],
+                share: 1,
+            },
+        ],
+        GridOptions {
+            width: 14,
+            indent: "> ".into(),
+            line_terminator: "\r\n".into(),
+            final_newline: false,
+            ..Default::default()
+        },
+    );
+    assert_eq!(
+        "> verylongname  xxx\r\n> b             yyy",
+        panes.to_string()
+    );
+}
+
+#[test]
+fn aspect_ratio() {
+    let cells: Vec<String> = ('a'..='p').map(String::from).collect();
+    let grid = |strategy| {
+        Grid::new(
+            cells.clone(),
+            GridOptions {
+                width: 80,
+                strategy,
+                ..Default::default()
+            },
+        )
+    };
+
+    assert_eq!(grid(Strategy::FewestLines).row_count(), 1);
+    assert_eq!(
+        "a  b  c  d\ne  f  g  h\ni  j  k  l\nm  n  o  p\n",
+        grid(Strategy::AspectRatio {
+            rows: 1,
+            columns: 1
+        })
+        .to_string()
+    );
+    assert_eq!(
+        grid(Strategy::AspectRatio {
+            rows: 1,
+            columns: 4
+        })
+        .row_count(),
+        2
+    );
+}
+
+#[test]
+fn avoid_widows() {
+    let grid = |avoid_widows| {
+        Grid::new(
+            vec!["one", "two", "six", "ten", "cat", "dog", "owl", "elk"],
+            GridOptions {
+                width: 16,
+                avoid_widows,
+                ..Default::default()
+            },
+        )
+        .to_string()
+    };
+
+    assert_eq!("one  two  six\nten  cat  dog\nowl  elk  \n", grid(false));
+    assert_eq!("one  two\nsix  ten\ncat  dog\nowl  elk\n", grid(true));
+}
+
+#[test]
+fn spanning_cells() {
+    let cells = vec![
+        Cell::new("Fruit:").with_span(3),
+        Cell::new("apple"),
+        Cell::new("pear"),
+        Cell::new("fig"),
+        Cell::new("Vegetables:").with_span(3),
+        Cell::new("kale"),
+        Cell::new("leek"),
+    ];
+    let grid = |width| {
+        Grid::new(
+            cells.clone(),
+            GridOptions {
+                width,
+                ..Default::default()
+            },
+        )
+    };
+
+    assert_eq!(
+        "Fruit:       apple  pear  fig\nVegetables:  kale   leek  \n",
+        grid(30).to_string()
+    );
+
+    // Spans are cut short at the edge of the grid.
+    let grid = grid(14);
+    assert_eq!(
+        "Fruit:\napple  pear\nfig    \nVegetables:\nkale   leek\n",
+        grid.to_string()
+    );
+    assert_eq!(
+        "┌───────┬──────┐\n│ Fruit:       │\n│ apple │ pear │\n│ fig   │      │\n│ Vegetables:  │\n│ kale  │ leek │\n└───────┴──────┘\n",
+        format!("{grid:#}")
+    );
+}
+
 // These test are based on the tests in uutils ls, to ensure we won't break
 // it while editing this library.
 mod uutils_ls {
@@ -239,6 +3202,7 @@ mod uutils_ls {
                     direction: Direction::TopToBottom,
                     filling: Filling::Spaces(2),
                     width,
+                    ..Default::default()
                 },
             );
             assert_eq!(expected, grid.to_string());
@@ -258,6 +3222,7 @@ mod uutils_ls {
                 direction: Direction::LeftToRight,
                 filling: Filling::Spaces(2),
                 width: 30,
+                ..Default::default()
             },
         );
 
@@ -280,6 +3245,7 @@ mod uutils_ls {
                 direction: Direction::TopToBottom,
                 filling: Filling::Spaces(2),
                 width: 30,
+                ..Default::default()
             },
         );
 
@@ -297,6 +3263,7 @@ mod uutils_ls {
                 direction: Direction::TopToBottom,
                 filling: Filling::Spaces(2),
                 width: 15,
+                ..Default::default()
             },
         );
 